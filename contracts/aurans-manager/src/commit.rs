@@ -0,0 +1,67 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+use sha2::Digest;
+
+use crate::{
+    error::ContractError,
+    msg::CommitmentMsg,
+    state::{commitments, Config},
+};
+
+// Max number of stale commitments removed in one execution
+pub const PRUNE_LIMIT: usize = 10;
+
+// Commitment is sha256 of the json serialized `CommitmentMsg`
+pub fn commitment_hash(name: &str, owner: &str, secret: &[u8]) -> Result<Vec<u8>, ContractError> {
+    let commitment_msg = CommitmentMsg {
+        name: name.to_owned(),
+        owner: owner.to_owned(),
+        secret: secret.to_vec().into(),
+    };
+    let commitment_msg_str =
+        serde_json_wasm::to_string(&commitment_msg).map_err(|_| ContractError::SerdeError)?;
+    Ok(sha2::Sha256::digest(commitment_msg_str).to_vec())
+}
+
+// Remove the commitments older than max commitment age, return number of removed commitments
+pub fn prune_commitments(storage: &mut dyn Storage, config: &Config, now: u64) -> StdResult<usize> {
+    let stale_before = now.saturating_sub(config.max_commitment_age);
+    let stale = commitments()
+        .idx
+        .committed_at
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive((stale_before, vec![]))),
+            Order::Ascending,
+        )
+        .take(PRUNE_LIMIT)
+        .collect::<StdResult<Vec<_>>>()?;
+    for commitment in &stale {
+        commitments().remove(storage, commitment)?;
+    }
+    Ok(stale.len())
+}
+
+// Check the revealed commitment is old enough but not expired, then remove it
+pub fn consume_commitment(
+    storage: &mut dyn Storage,
+    config: &Config,
+    name: &str,
+    owner: &Addr,
+    secret: &[u8],
+    now: u64,
+) -> Result<(), ContractError> {
+    let commitment = commitment_hash(name, owner.as_str(), secret)?;
+    let committed_at = commitments()
+        .may_load(storage, &commitment)?
+        .ok_or(ContractError::CommitmentNotFound)?;
+    if now < committed_at + config.min_commitment_age {
+        return Err(ContractError::CommitmentTooNew);
+    }
+    if now > committed_at + config.max_commitment_age {
+        return Err(ContractError::CommitmentExpired);
+    }
+    commitments().remove(storage, &commitment)?;
+    Ok(())
+}
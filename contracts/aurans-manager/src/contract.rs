@@ -1,26 +1,48 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
-    QueryRequest, Reply, ReplyOn, Response, StdResult, SubMsg, WasmMsg, WasmQuery,
+    from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, Event,
+    MessageInfo, Order, QueryRequest, Reply, ReplyOn, Response, StdError, StdResult, SubMsg,
+    WasmMsg, WasmQuery,
 };
 use cw2::set_contract_version;
-use cw721::NftInfoResponse;
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg, Denom};
+use cw721::AllNftInfoResponse;
+use cw_storage_plus::Bound;
 use cw_utils::parse_reply_instantiate_data;
 
+use crate::commit::{commitment_hash, consume_commitment, prune_commitments};
 use crate::error::ContractError;
-use crate::price::{calc_price, check_fee};
-use crate::state::{Config, Verifier, CONFIG, NAME_CONTRACT, PRICE_INFO, REGISTERS, VERIFIER};
+use crate::price::{
+    amount_to_string, check_durations, check_fee, payment_denom, quote_fee, refund_msgs, Payment,
+};
+use crate::revenue::{available_balance, settle_fee, split_revenue, transfer_msg};
+use crate::state::{
+    commitments, Config, ReferralReward, RegistrationMode, Revenue, RevenueShare, Verifier, BURNED,
+    CONFIG, CW20_PRICE_INFO, NAME_CONTRACT, NONCES, PRICE_INFO, REFERRAL_RATES, REFERRAL_REWARDS,
+    REGISTERS, RESERVED, REVENUES, REVENUE_SHARES, THRESHOLDS, UNCLAIMED_REFERRAL_REWARDS,
+    VERIFIERS, YEAR_DISCOUNTS,
+};
 
-use crate::util::sec_to_years;
-use crate::verify::verify_signature;
+use crate::util::{registration_status, year_to_secs, BPS_DENOMINATOR};
+use crate::verify::{check_verifier, verify_signature};
+use aurans_name::policy::NamePolicy;
 use aurans_name::state::Metadata;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:aurans-manager";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, PricesResponse, QueryMsg, VerifyMsg};
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+// Max number of names registered in one batch
+const DEFAULT_LIMIT_BATCH: usize = 10;
+
+use crate::msg::{
+    BackendSignature, Cw20PricesResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PricesResponse,
+    QueryMsg, QuoteResponse, ReceiveMsg, RegisterItem, RegistrationStatus,
+    RegistrationStatusResponse, ReservedName, ReservedNamesResponse, VerifyMsg, WithdrawCoin,
+};
 
 /// Handling contract instantiation
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -32,6 +54,14 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    if msg.burn_bps > BPS_DENOMINATOR
+        || msg.min_durations == 0
+        || msg.min_durations > year_to_secs(msg.max_year_register)
+        || msg.min_commitment_age > msg.max_commitment_age
+    {
+        return Err(ContractError::InvalidArguments);
+    }
+
     // save contract config
     let config = Config {
         admin: deps.api.addr_validate(&msg.admin)?,
@@ -39,17 +69,28 @@ pub fn instantiate(
         name_code_id: msg.name_code_id,
         resolver_code_id: msg.resolver_code_id,
         max_year_register: msg.max_year_register,
+        min_durations: msg.min_durations,
+        grace_period: msg.grace_period,
+        redemption_period: msg.redemption_period,
+        redemption_fee_bps: msg.redemption_fee_bps,
+        premium_start: msg.premium_start.clone(),
+        premium_period: msg.premium_period,
+        min_commitment_age: msg.min_commitment_age,
+        max_commitment_age: msg.max_commitment_age,
+        registration_mode: msg.registration_mode.clone(),
+        price_floor_bps: msg.price_floor_bps,
+        burn_bps: msg.burn_bps,
     };
     CONFIG.save(deps.storage, &config)?;
 
     for (l, price) in &msg.prices {
-        PRICE_INFO.save(deps.storage, *l, &price)?;
+        PRICE_INFO.save(deps.storage, (*l, &price.denom), &price.amount)?;
     }
 
-    let verifier = Verifier {
-        backend_pubkey: msg.backend_pubkey.clone(),
-    };
-    VERIFIER.save(deps.storage, &verifier)?;
+    for verifier in &msg.verifiers {
+        check_verifier(verifier)?;
+        VERIFIERS.save(deps.storage, &verifier.key_id, verifier)?;
+    }
 
     let name_ins_msg = CosmosMsg::Wasm(WasmMsg::Instantiate {
         admin: Some(env.contract.address.to_string()),
@@ -58,6 +99,7 @@ pub fn instantiate(
             admin: config.admin.to_string(),
             minter: env.contract.address.to_string(),
             resolver_code_id: config.resolver_code_id,
+            name_policy: msg.name_policy,
         })?,
         funds: vec![],
         label: "aurans-name".to_owned(),
@@ -73,15 +115,33 @@ pub fn instantiate(
     Ok(Response::new()
         .add_submessage(name_sub_msg)
         .add_attribute("action", "instantiate")
-        .add_attribute("backend_pubkey", msg.backend_pubkey.to_string())
+        .add_attribute(
+            "verifiers",
+            msg.verifiers
+                .iter()
+                .map(|verifier| verifier.key_id.clone())
+                .collect::<Vec<String>>()
+                .join(","),
+        )
         .add_attribute("name_code_id", msg.name_code_id.to_string())
         .add_attribute("resolver_code_id", msg.resolver_code_id.to_string())
         .add_attribute("max_year_register", msg.max_year_register.to_string())
+        .add_attribute("min_durations", msg.min_durations.to_string())
+        .add_attribute("grace_period", msg.grace_period.to_string())
+        .add_attribute("redemption_period", msg.redemption_period.to_string())
+        .add_attribute("redemption_fee_bps", msg.redemption_fee_bps.to_string())
+        .add_attribute("premium_start", coins_to_string(&msg.premium_start))
+        .add_attribute("premium_period", msg.premium_period.to_string())
+        .add_attribute("min_commitment_age", msg.min_commitment_age.to_string())
+        .add_attribute("max_commitment_age", msg.max_commitment_age.to_string())
+        .add_attribute("registration_mode", msg.registration_mode.to_string())
+        .add_attribute("price_floor_bps", msg.price_floor_bps.to_string())
+        .add_attribute("burn_bps", msg.burn_bps.to_string())
         .add_attribute(
             "prices",
             msg.prices
                 .iter()
-                .map(|(l, price)| format!("{}:{}", l, price.to_string()))
+                .map(|(l, price)| format!("{}:{}", l, price))
                 .collect::<Vec<String>>()
                 .join(","),
         ))
@@ -111,6 +171,17 @@ pub fn execute(
             name_code_id,
             resolver_code_id,
             max_year_register,
+            min_durations,
+            grace_period,
+            redemption_period,
+            redemption_fee_bps,
+            premium_start,
+            premium_period,
+            min_commitment_age,
+            max_commitment_age,
+            registration_mode,
+            price_floor_bps,
+            burn_bps,
         } => execute_update_config(
             deps,
             env,
@@ -120,49 +191,342 @@ pub fn execute(
             name_code_id,
             resolver_code_id,
             max_year_register,
+            min_durations,
+            grace_period,
+            redemption_period,
+            redemption_fee_bps,
+            premium_start,
+            premium_period,
+            min_commitment_age,
+            max_commitment_age,
+            registration_mode,
+            price_floor_bps,
+            burn_bps,
         ),
         ExecuteMsg::UpdatePrices { prices } => execute_update_prices(deps, env, info, prices),
-        ExecuteMsg::UpdateVerifier { backend_pubkey } => {
-            execute_update_verifier(deps, env, info, backend_pubkey)
+        ExecuteMsg::RemovePrices { prices } => execute_remove_prices(deps, env, info, prices),
+        ExecuteMsg::UpdateCw20Prices { prices } => {
+            execute_update_cw20_prices(deps, env, info, prices)
+        }
+        ExecuteMsg::RemoveCw20Prices { prices } => {
+            execute_remove_cw20_prices(deps, env, info, prices)
+        }
+        ExecuteMsg::UpdateVerifier { verifier } => {
+            execute_update_verifier(deps, env, info, verifier)
+        }
+        ExecuteMsg::RemoveVerifier { key_id } => execute_remove_verifier(deps, env, info, key_id),
+        ExecuteMsg::UpdateYearDiscounts { discounts } => {
+            execute_update_year_discounts(deps, env, info, discounts)
+        }
+        ExecuteMsg::UpdateThresholds { thresholds } => {
+            execute_update_thresholds(deps, env, info, thresholds)
         }
+        ExecuteMsg::ReserveNames { names } => execute_reserve_names(deps, env, info, names),
+        ExecuteMsg::UnreserveNames { names } => execute_unreserve_names(deps, env, info, names),
+        ExecuteMsg::Commit { commitment } => execute_commit(deps, env, info, commitment),
         ExecuteMsg::Register {
             name,
             backend_signature,
             metadata,
-        } => execute_register(deps, env, info, name, backend_signature, metadata),
+            secret,
+            owner,
+            referrer,
+        } => execute_register(
+            deps,
+            env,
+            info.sender,
+            Payment::Native(info.funds),
+            name,
+            backend_signature,
+            metadata,
+            secret,
+            owner,
+            referrer,
+        ),
+        ExecuteMsg::RegisterBatch { items, referrer } => {
+            execute_register_batch(deps, env, info, items, referrer)
+        }
         ExecuteMsg::Extend {
             name,
             backend_signature,
             durations,
-        } => execute_extend(deps, env, info, name, backend_signature, durations),
+            referrer,
+        } => execute_extend(
+            deps,
+            env,
+            info.sender,
+            Payment::Native(info.funds),
+            name,
+            backend_signature,
+            durations,
+            referrer,
+        ),
+        ExecuteMsg::ExtendBatch { names, referrer } => {
+            execute_extend_batch(deps, env, info, names, referrer)
+        }
         ExecuteMsg::Unregister { names } => execute_unregister(deps, env, info, names),
         ExecuteMsg::Withdraw { receiver, coin } => {
             execute_withdraw(deps, env, info, receiver, coin)
         }
+        ExecuteMsg::UpdateRevenueShares { shares } => {
+            execute_update_revenue_shares(deps, env, info, shares)
+        }
+        ExecuteMsg::Distribute {} => execute_distribute(deps, env, info),
+        ExecuteMsg::UpdateReferralRates { rates } => {
+            execute_update_referral_rates(deps, env, info, rates)
+        }
+        ExecuteMsg::ClaimReferralRewards {} => execute_claim_referral_rewards(deps, env, info),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+    }
+}
+
+// Register or extend paid with cw20 tokens, info sender is the token contract
+fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    let payment = Payment::Cw20(Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapper.amount,
+    });
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::Register {
+            name,
+            backend_signature,
+            metadata,
+            secret,
+            owner,
+            referrer,
+        } => execute_register(
+            deps,
+            env,
+            sender,
+            payment,
+            name,
+            backend_signature,
+            metadata,
+            secret,
+            owner,
+            referrer,
+        ),
+        ReceiveMsg::Extend {
+            name,
+            backend_signature,
+            durations,
+            referrer,
+        } => execute_extend(
+            deps,
+            env,
+            sender,
+            payment,
+            name,
+            backend_signature,
+            durations,
+            referrer,
+        ),
     }
 }
 
 fn execute_withdraw(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     receiver: String,
-    coin: Coin,
+    coin: WithdrawCoin,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     if info.sender != config.admin {
         return Err(ContractError::Unauthorized {});
     }
     let receiver_addr = deps.api.addr_validate(&receiver)?;
-    let withdraw_msg = BankMsg::Send {
-        to_address: receiver_addr.to_string(),
-        amount: vec![coin.clone()],
+    let (denom, withdraw_amount) = match &coin {
+        WithdrawCoin::Native(coin) => (Denom::Native(coin.denom.clone()), coin.amount),
+        WithdrawCoin::Cw20(coin) => (
+            Denom::Cw20(deps.api.addr_validate(&coin.address)?),
+            coin.amount,
+        ),
+    };
+    // Unclaimed commissions are kept for referrers to claim
+    let available = available_balance(deps.as_ref(), &env.contract.address, &denom)?;
+    if withdraw_amount > available {
+        return Err(ContractError::WithdrawExceedsBalance {
+            available: amount_to_string(available, &denom),
+        });
+    }
+    let (withdraw_msg, amount): (CosmosMsg, String) = match coin {
+        WithdrawCoin::Native(coin) => (
+            BankMsg::Send {
+                to_address: receiver_addr.to_string(),
+                amount: vec![coin.clone()],
+            }
+            .into(),
+            coin.to_string(),
+        ),
+        WithdrawCoin::Cw20(coin) => (
+            WasmMsg::Execute {
+                contract_addr: coin.address,
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: receiver_addr.to_string(),
+                    amount: coin.amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+            amount_to_string(coin.amount, &denom),
+        ),
     };
     Ok(Response::new()
         .add_message(withdraw_msg)
         .add_attribute("action", "withdraw")
         .add_attribute("receiver", receiver)
-        .add_attribute("amount", coin.to_string()))
+        .add_attribute("amount", amount))
+}
+
+fn execute_update_revenue_shares(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    shares: Vec<(String, u64)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let revenue_shares = shares
+        .iter()
+        .map(|(recipient, bps)| {
+            Ok(RevenueShare {
+                recipient: deps.api.addr_validate(recipient)?,
+                bps: *bps,
+            })
+        })
+        .collect::<StdResult<Vec<RevenueShare>>>()?;
+    let total_bps: u64 = revenue_shares.iter().map(|share| share.bps).sum();
+    if total_bps != BPS_DENOMINATOR {
+        return Err(ContractError::InvalidRevenueShares);
+    }
+    REVENUE_SHARES.save(deps.storage, &revenue_shares)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_revenue_shares")
+        .add_attribute(
+            "shares",
+            shares
+                .iter()
+                .map(|(recipient, bps)| format!("{}:{}", recipient, bps))
+                .collect::<Vec<String>>()
+                .join(","),
+        ))
+}
+
+fn execute_update_referral_rates(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    rates: Vec<(String, u64)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for (referrer, rate) in &rates {
+        if *rate > BPS_DENOMINATOR {
+            return Err(ContractError::InvalidArguments);
+        }
+        let referrer = deps.api.addr_validate(referrer)?;
+        if *rate == 0 {
+            REFERRAL_RATES.remove(deps.storage, &referrer);
+        } else {
+            REFERRAL_RATES.save(deps.storage, &referrer, rate)?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_referral_rates")
+        .add_attribute(
+            "rates",
+            rates
+                .iter()
+                .map(|(referrer, rate)| format!("{}:{}", referrer, rate))
+                .collect::<Vec<String>>()
+                .join(","),
+        ))
+}
+
+fn execute_claim_referral_rewards(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let rewards = REFERRAL_REWARDS
+        .prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut msgs = Vec::new();
+    let mut claimed = Vec::new();
+    for (key, mut reward) in rewards {
+        let amount = reward.accrued - reward.claimed;
+        if amount.is_zero() {
+            continue;
+        }
+        msgs.push(transfer_msg(&reward.denom, &info.sender, amount)?);
+        reward.claimed += amount;
+        REFERRAL_REWARDS.save(deps.storage, (&info.sender, &key), &reward)?;
+        UNCLAIMED_REFERRAL_REWARDS.update(deps.storage, &key, |unclaimed| -> StdResult<_> {
+            Ok(unclaimed.unwrap_or_default() - amount)
+        })?;
+        claimed.push(amount_to_string(amount, &reward.denom));
+    }
+    if msgs.is_empty() {
+        return Err(ContractError::NoReferralRewards);
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "claim_referral_rewards")
+        .add_attribute("referrer", info.sender)
+        .add_attribute("claimed", claimed.join(",")))
+}
+
+// Split the balance of every denom fees were paid in to the revenue share recipients
+fn execute_distribute(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let shares = REVENUE_SHARES
+        .may_load(deps.storage)?
+        .ok_or(ContractError::InvalidRevenueShares)?;
+
+    let revenues = REVENUES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut msgs = Vec::new();
+    let mut distributed = Vec::new();
+    for (key, mut revenue) in revenues {
+        let balance = available_balance(deps.as_ref(), &env.contract.address, &revenue.denom)?;
+        if balance.is_zero() {
+            continue;
+        }
+        for (recipient, amount) in split_revenue(&shares, balance) {
+            if !amount.is_zero() {
+                msgs.push(transfer_msg(&revenue.denom, &recipient, amount)?);
+            }
+        }
+        revenue.distributed += balance;
+        REVENUES.save(deps.storage, &key, &revenue)?;
+        distributed.push(amount_to_string(balance, &revenue.denom));
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "distribute")
+        .add_attribute("distributed", distributed.join(",")))
 }
 
 fn execute_unregister(
@@ -201,162 +565,500 @@ fn execute_unregister(
         .add_attribute("names", names.join(",")))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_extend(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
+    sender: Addr,
+    payment: Payment,
     name: String,
-    backend_signature: Binary,
+    backend_signature: Option<BackendSignature>,
     durations: u64,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
+    let (res, change) = extend_name(
+        deps,
+        &env,
+        sender.clone(),
+        payment,
+        name,
+        backend_signature,
+        durations,
+        referrer,
+    )?;
+    Ok(res.add_messages(refund_msgs(&sender, change)?))
+}
+
+// Extend names paid by the funds once, names are extended without backend
+// signatures so the sender must be allowed to renew them by the registration mode
+fn execute_extend_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    names: Vec<(String, u64)>,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    if names.is_empty() {
+        return Err(ContractError::InvalidArguments);
+    }
+    if names.len() > DEFAULT_LIMIT_BATCH {
+        return Err(ContractError::BatchTooLong);
+    }
+    // Every name is extended from its token before the batch, so it is extended once
+    let name_contract = NAME_CONTRACT.load(deps.storage)?;
+    let name_policy = query_name_policy(deps.as_ref(), &name_contract)?;
+    let mut normalized = names
+        .iter()
+        .map(|(name, _)| name_policy.normalize(name))
+        .collect::<Vec<_>>();
+    normalized.sort();
+    normalized.dedup();
+    if normalized.len() != names.len() {
+        return Err(ContractError::InvalidArguments);
+    }
+
+    let mut payment = Payment::Native(info.funds);
+    let mut res = Response::new()
+        .add_attribute("action", "extend_batch")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("count", names.len().to_string());
+    for (name, durations) in names {
+        let (item_res, change) = extend_name(
+            deps.branch(),
+            &env,
+            info.sender.clone(),
+            payment,
+            name,
+            None,
+            durations,
+            referrer.clone(),
+        )?;
+        payment = change;
+        res = res
+            .add_submessages(item_res.messages)
+            .add_event(Event::new("extend").add_attributes(item_res.attributes));
+    }
+    Ok(res.add_messages(refund_msgs(&info.sender, payment)?))
+}
+
+// Extend a name charged from payment, return the response and the change
+#[allow(clippy::too_many_arguments)]
+fn extend_name(
+    mut deps: DepsMut,
+    env: &Env,
+    sender: Addr,
+    payment: Payment,
+    name: String,
+    backend_signature: Option<BackendSignature>,
+    durations: u64,
+    referrer: Option<String>,
+) -> Result<(Response, Payment), ContractError> {
+    let name_contract = NAME_CONTRACT.load(deps.storage)?;
+    let name = query_name_policy(deps.as_ref(), &name_contract)?.normalize(&name);
     if !REGISTERS.has(deps.storage, &name) {
         return Err(ContractError::NameNotRegistered { name });
     }
     let old_expires = REGISTERS.load(deps.storage, &name)?;
     let config = CONFIG.load(deps.storage)?;
 
-    let years = sec_to_years(durations);
-    if years == 0 {
-        return Err(ContractError::InvalidDurations);
+    let status = registration_status(&config, old_expires, env.block.time.seconds());
+    if status == RegistrationStatus::Available {
+        return Err(ContractError::NameExpired { name });
     }
-    if years > config.max_year_register as u64 {
-        return Err(ContractError::LimitYearRegister);
+
+    check_durations(&config, durations)?;
+
+    let old_token: AllNftInfoResponse<Metadata> =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: name_contract.to_string(),
+            msg: to_binary(&aurans_name::QueryMsg::AllNftInfo {
+                token_id: name.clone(),
+                include_expired: None,
+            })?,
+        }))?;
+
+    // Only owner can extend an expired name in grace or redemption period
+    if status != RegistrationStatus::Active
+        && config.admin != sender
+        && old_token.access.owner != sender
+    {
+        return Err(ContractError::Unauthorized {});
     }
 
-    // Check user funds
-    let fee = calc_price(deps.as_ref(), &name, &years)?;
-    check_fee(fee, &info.funds)?;
+    let denom = payment_denom(deps.as_ref(), &name, &payment)?;
 
-    // If not owner, check verification msg
-    if config.admin != info.sender {
+    // Owners renew without the backend, others depend on the registration mode
+    let signature_required = config.admin != sender
+        && old_token.access.owner != sender
+        && config.registration_mode == RegistrationMode::SignatureRequired;
+    let mut signed_price = None;
+    if let Some(backend_signature) = backend_signature
+        .as_ref()
+        .filter(|_| config.admin != sender)
+    {
         let verify_msg = VerifyMsg::Extend {
             name: name.clone(),
-            sender: info.sender.to_string(),
-            chain_id: env.block.chain_id,
+            sender: sender.to_string(),
+            chain_id: env.block.chain_id.clone(),
+            contract: env.contract.address.to_string(),
             durations,
+            nonce: backend_signature.nonce,
+            valid_until: backend_signature.valid_until,
+            price: backend_signature.price.clone(),
         };
-        let verify_msg_str =
-            serde_json_wasm::to_string(&verify_msg).map_err(|_| ContractError::SerdeError)?;
-
-        let verifier = VERIFIER.load(deps.storage)?;
-
-        verify_signature(
-            deps.as_ref(),
-            &verify_msg_str,
-            &backend_signature.as_slice(),
-            &verifier.backend_pubkey,
-        )?;
+        verify_signature(deps.branch(), env, &sender, &verify_msg, backend_signature)?;
+        signed_price = backend_signature.price.clone();
+    } else if signature_required {
+        return Err(ContractError::SignatureRequired);
     }
-    // Get name contract
-    let name_contract = NAME_CONTRACT.load(deps.storage)?;
-    let new_expires = old_expires + durations;
 
-    let old_token: NftInfoResponse<Metadata> =
-        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: name_contract.to_string(),
-            msg: to_binary(&aurans_name::QueryMsg::NftInfo {
-                token_id: name.clone(),
-            })?,
-        }))?;
+    // Check user funds
+    let quote = quote_fee(
+        deps.as_ref(),
+        &config,
+        &name,
+        durations,
+        &denom,
+        Some(old_expires),
+        env.block.time.seconds(),
+        signed_price.as_ref(),
+    )?;
+    let fee = quote.total;
+    let change = check_fee(fee, &denom, payment)?;
 
-    // Burn old name
-    let burn_msg = WasmMsg::Execute {
-        contract_addr: name_contract.to_string(),
-        msg: to_binary(&aurans_name::ExecuteMsg::Burn {
-            token_id: name.clone(),
-        })?,
-        funds: vec![],
-    };
+    let (burn_msgs, burned, referral) = settle_fee(
+        deps.storage,
+        deps.api,
+        &config,
+        &denom,
+        fee,
+        referrer,
+        &sender,
+    )?;
 
-    let mut extension = old_token.extension;
-    extension.durations = durations;
+    let new_expires = old_expires + durations;
 
-    // Mint new name
-    let mint_msg = WasmMsg::Execute {
+    // Renew the name in place for its owner, durations is the lifetime of the name
+    // so expires stays its register time plus durations
+    let renew_msg = WasmMsg::Execute {
         contract_addr: name_contract.to_string(),
-        msg: to_binary(&aurans_name::ExecuteMsg::Mint {
-            token_id: name.clone(),
-            owner: info.sender.clone().to_string(),
-            token_uri: old_token.token_uri,
-            extension,
+        msg: to_binary(&aurans_name::ExecuteMsg::Extension {
+            msg: aurans_name::NameExecuteMsg::Renew {
+                token_id: name.clone(),
+                expires: new_expires,
+                durations,
+            },
         })?,
         funds: vec![],
     };
 
     REGISTERS.save(deps.storage, &name, &new_expires)?;
 
-    Ok(Response::new()
-        .add_message(burn_msg)
-        .add_message(mint_msg)
+    let mut res = Response::new()
+        .add_message(renew_msg)
+        .add_messages(burn_msgs)
         .add_attribute("action", "extend")
-        .add_attribute("sender", info.sender)
+        .add_attribute("sender", sender)
         .add_attribute("name", name)
+        .add_attribute("status", status.to_string())
         .add_attribute("new_expires", new_expires.to_string())
-        .add_attribute("durations", durations.to_string()))
+        .add_attribute("durations", durations.to_string())
+        .add_attribute("fee", amount_to_string(fee, &denom))
+        .add_attribute("redemption_fee", quote.redemption_fee.to_string())
+        .add_attribute("burned", amount_to_string(burned, &denom));
+    if signed_price.is_some() {
+        res = res.add_attribute("discount", amount_to_string(quote.discount, &denom));
+    }
+    if let Some((referrer, commission)) = referral {
+        res = res
+            .add_attribute("referrer", referrer)
+            .add_attribute("commission", amount_to_string(commission, &denom));
+    }
+    Ok((res, change))
 }
 
-fn execute_register(
+fn execute_reserve_names(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    names: Vec<(String, Option<String>)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let name_contract = NAME_CONTRACT.load(deps.storage)?;
+    let name_policy = query_name_policy(deps.as_ref(), &name_contract)?;
+    let mut reserved = Vec::new();
+    for (name, claimant) in names {
+        let name = name_policy.normalize(&name);
+        let claimant = claimant
+            .map(|claimant| deps.api.addr_validate(&claimant))
+            .transpose()?;
+        RESERVED.save(deps.storage, &name, &claimant)?;
+        reserved.push(match claimant {
+            Some(claimant) => format!("{}:{}", name, claimant),
+            None => name,
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "reserve_names")
+        .add_attribute("names", reserved.join(",")))
+}
+
+fn execute_unreserve_names(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    names: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let name_contract = NAME_CONTRACT.load(deps.storage)?;
+    let name_policy = query_name_policy(deps.as_ref(), &name_contract)?;
+    let names = names
+        .iter()
+        .map(|name| name_policy.normalize(name))
+        .collect::<Vec<_>>();
+    for name in &names {
+        RESERVED.remove(deps.storage, name);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "unreserve_names")
+        .add_attribute("names", names.join(",")))
+}
+
+fn execute_commit(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    commitment: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    let pruned = prune_commitments(deps.storage, &config, now)?;
+
+    // A commitment can not be renewed until it is stale
+    if commitments().has(deps.storage, &commitment) {
+        return Err(ContractError::CommitmentExists);
+    }
+    commitments().save(deps.storage, &commitment, &now)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "commit")
+        .add_attribute("sender", info.sender)
+        .add_attribute("commitment", commitment.to_string())
+        .add_attribute("committed_at", now.to_string())
+        .add_attribute("pruned", pruned.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_register(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    payment: Payment,
     name: String,
-    backend_signature: Binary,
+    backend_signature: Option<BackendSignature>,
     metadata: Metadata,
+    secret: Option<Binary>,
+    owner: Option<String>,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
-    // Check name is registed or not
-    if REGISTERS.has(deps.storage, &name) {
-        return Err(ContractError::NameRegistered { name });
+    let item = RegisterItem {
+        name,
+        backend_signature,
+        metadata,
+        secret,
+        owner,
+    };
+    let (res, change) = register_name(deps, &env, sender.clone(), payment, item, referrer)?;
+    Ok(res.add_messages(refund_msgs(&sender, change)?))
+}
+
+// Register names paid by the funds once, every name is checked and charged in turn
+// and the remaining funds are refunded
+fn execute_register_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    items: Vec<RegisterItem>,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    if items.is_empty() {
+        return Err(ContractError::InvalidArguments);
+    }
+    if items.len() > DEFAULT_LIMIT_BATCH {
+        return Err(ContractError::BatchTooLong);
+    }
+
+    let mut payment = Payment::Native(info.funds);
+    let mut res = Response::new()
+        .add_attribute("action", "register_batch")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("count", items.len().to_string());
+    for item in items {
+        let (item_res, change) = register_name(
+            deps.branch(),
+            &env,
+            info.sender.clone(),
+            payment,
+            item,
+            referrer.clone(),
+        )?;
+        payment = change;
+        res = res
+            .add_submessages(item_res.messages)
+            .add_event(Event::new("register").add_attributes(item_res.attributes));
     }
+    Ok(res.add_messages(refund_msgs(&info.sender, payment)?))
+}
 
+// Register a name charged from payment, return the response and the change
+fn register_name(
+    mut deps: DepsMut,
+    env: &Env,
+    sender: Addr,
+    payment: Payment,
+    item: RegisterItem,
+    referrer: Option<String>,
+) -> Result<(Response, Payment), ContractError> {
+    let RegisterItem {
+        name,
+        backend_signature,
+        metadata,
+        secret,
+        owner,
+    } = item;
     let config = CONFIG.load(deps.storage)?;
+
+    // Normalize and validate name by the policy of name contract
+    let name_contract = NAME_CONTRACT.load(deps.storage)?;
+    let name_policy = query_name_policy(deps.as_ref(), &name_contract)?;
+    let name = name_policy.normalize(&name);
+    if !name_policy.is_valid(&name) {
+        return Err(ContractError::InvalidName { name });
+    }
+
+    // Reserved names can only be registered by the claimant or admin
+    if let Some(claimant) = RESERVED.may_load(deps.storage, &name)? {
+        if config.admin != sender && claimant.as_ref() != Some(&sender) {
+            return Err(ContractError::NameReserved { name });
+        }
+    }
+
+    // Check name is registed or not, expired names are kept for the owner
+    // until the grace and redemption periods are over
+    let register_secs = env.block.time.seconds();
+    let old_expires = REGISTERS.may_load(deps.storage, &name)?;
+    if let Some(expires) = old_expires {
+        if registration_status(&config, expires, register_secs) != RegistrationStatus::Available {
+            return Err(ContractError::NameRegistered { name });
+        }
+    }
     let durations = metadata.durations;
-    let years = sec_to_years(durations);
-    if years == 0 {
-        return Err(ContractError::InvalidDurations);
+    check_durations(&config, durations)?;
+
+    // Reveal the commitment to register the name for the committed owner, non admin
+    // senders must reveal a matured commitment so the name can not be front-run
+    if owner.is_some() && secret.is_none() {
+        return Err(ContractError::CommitmentRequired);
     }
-    if years > config.max_year_register {
-        return Err(ContractError::LimitYearRegister);
+    let owner = match owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => sender.clone(),
+    };
+    match &secret {
+        Some(secret) => {
+            consume_commitment(deps.storage, &config, &name, &owner, secret, register_secs)?
+        }
+        None if config.admin != sender && config.min_commitment_age > 0 => {
+            return Err(ContractError::CommitmentRequired);
+        }
+        None => {}
     }
 
-    // Check fee
-    let fee = calc_price(deps.as_ref(), &name, &years)?;
-    check_fee(fee, &info.funds)?;
+    let denom = payment_denom(deps.as_ref(), &name, &payment)?;
 
     let bech32_prefixes = metadata.bech32_prefixes;
 
-    // If not owner, check verification msg
-    if config.admin != info.sender {
+    // Check verification msg, open registration only requires it when given
+    let signature_required =
+        config.admin != sender && config.registration_mode != RegistrationMode::Open;
+    let mut signed_price = None;
+    if let Some(backend_signature) = backend_signature
+        .as_ref()
+        .filter(|_| config.admin != sender)
+    {
         let verify_msg = VerifyMsg::Register {
             name: name.clone(),
-            sender: info.sender.to_string(),
-            chain_id: env.block.chain_id,
+            sender: sender.to_string(),
+            chain_id: env.block.chain_id.clone(),
+            contract: env.contract.address.to_string(),
             bech32_prefixes: bech32_prefixes.clone(),
             durations,
+            nonce: backend_signature.nonce,
+            valid_until: backend_signature.valid_until,
+            price: backend_signature.price.clone(),
         };
-        let verify_msg_str =
-            serde_json_wasm::to_string(&verify_msg).map_err(|_| ContractError::SerdeError)?;
+        verify_signature(deps.branch(), env, &sender, &verify_msg, backend_signature)?;
+        signed_price = backend_signature.price.clone();
+    } else if signature_required {
+        return Err(ContractError::SignatureRequired);
+    }
 
-        let verifier = VERIFIER.load(deps.storage)?;
+    // Check fee
+    let quote = quote_fee(
+        deps.as_ref(),
+        &config,
+        &name,
+        durations,
+        &denom,
+        old_expires,
+        register_secs,
+        signed_price.as_ref(),
+    )?;
+    let fee = quote.total;
+    let change = check_fee(fee, &denom, payment)?;
 
-        verify_signature(
-            deps.as_ref(),
-            &verify_msg_str,
-            &backend_signature.as_slice(),
-            &verifier.backend_pubkey,
-        )?;
-    }
+    let (burn_msgs, burned, referral) = settle_fee(
+        deps.storage,
+        deps.api,
+        &config,
+        &denom,
+        fee,
+        referrer,
+        &sender,
+    )?;
 
-    let register_secs = env.block.time.seconds();
     let expires_secs = register_secs + durations;
 
+    let mut msgs = Vec::new();
+
+    // Burn the stale token of expired name, this also clears its resolver records
+    if old_expires.is_some() {
+        msgs.push(WasmMsg::Execute {
+            contract_addr: name_contract.to_string(),
+            msg: to_binary(&aurans_name::ExecuteMsg::Burn {
+                token_id: name.clone(),
+            })?,
+            funds: vec![],
+        });
+    }
+
     // Call mint msg
-    let name_contract = NAME_CONTRACT.load(deps.storage)?;
 
     let mint_msg = WasmMsg::Execute {
         contract_addr: name_contract.to_string(),
         msg: to_binary(&aurans_name::ExecuteMsg::Mint {
             token_id: name.clone(),
-            owner: info.sender.to_string(),
+            owner: owner.to_string(),
             token_uri: None,
             extension: Metadata {
                 image: metadata.image,
@@ -372,46 +1074,164 @@ fn execute_register(
                 royalty_payment_address: metadata.royalty_payment_address,
                 bech32_prefixes: bech32_prefixes.clone(),
                 durations,
+                expires: Some(expires_secs),
                 collection_name: metadata.collection_name,
                 collection_symbol: metadata.collection_symbol,
             },
         })?,
         funds: vec![],
     };
+    msgs.push(mint_msg);
 
     REGISTERS.save(deps.storage, &name, &expires_secs)?;
 
-    Ok(Response::new()
-        .add_message(mint_msg)
+    let mut res = Response::new()
+        .add_messages(msgs)
+        .add_messages(burn_msgs)
         .add_attribute("action", "register")
-        .add_attribute("sender", info.sender)
+        .add_attribute("sender", sender)
+        .add_attribute("owner", owner)
         .add_attribute("name", name)
         .add_attribute("bech32_prefixes", bech32_prefixes.join(","))
         .add_attribute("durations", durations.to_string())
         .add_attribute("register_at", register_secs.to_string())
         .add_attribute("expires_at", expires_secs.to_string())
-        .add_attribute("backend_signature", backend_signature.to_string()))
+        .add_attribute("fee", amount_to_string(fee, &denom))
+        .add_attribute("premium", quote.premium.to_string())
+        .add_attribute("burned", amount_to_string(burned, &denom));
+    if signed_price.is_some() {
+        res = res.add_attribute("discount", amount_to_string(quote.discount, &denom));
+    }
+    if let Some((referrer, commission)) = referral {
+        res = res
+            .add_attribute("referrer", referrer)
+            .add_attribute("commission", amount_to_string(commission, &denom));
+    }
+    // Admin and open registration go without backend signatures
+    if let Some(backend_signature) = backend_signature.filter(|s| !s.signatures.is_empty()) {
+        res = res.add_attribute(
+            "backend_signature",
+            backend_signature
+                .signatures
+                .iter()
+                .map(|key_signature| key_signature.signature.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+        );
+    }
+    Ok((res, change))
 }
 
 fn execute_update_verifier(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    backend_pubkey: Binary,
+    verifier: Verifier,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     if config.admin != info.sender {
         return Err(ContractError::Unauthorized {});
     }
-    VERIFIER.save(
-        deps.storage,
-        &Verifier {
-            backend_pubkey: backend_pubkey.clone(),
-        },
-    )?;
-    Ok(Response::new()
+    check_verifier(&verifier)?;
+    VERIFIERS.save(deps.storage, &verifier.key_id, &verifier)?;
+    let mut res = Response::new()
         .add_attribute("action", "update_verifier")
-        .add_attribute("backend_pubkey", backend_pubkey.to_string()))
+        .add_attribute("key_id", verifier.key_id)
+        .add_attribute("backend_pubkey", verifier.backend_pubkey.to_string())
+        .add_attribute("algorithm", verifier.algorithm.to_string())
+        .add_attribute("not_before", verifier.not_before.to_string());
+    if let Some(not_after) = verifier.not_after {
+        res = res.add_attribute("not_after", not_after.to_string());
+    }
+    Ok(res)
+}
+
+// Revoke a verifier key, signatures of the key are rejected immediately
+fn execute_remove_verifier(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    key_id: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !VERIFIERS.has(deps.storage, &key_id) {
+        return Err(ContractError::VerifierNotFound { key_id });
+    }
+    VERIFIERS.remove(deps.storage, &key_id);
+    Ok(Response::new()
+        .add_attribute("action", "remove_verifier")
+        .add_attribute("key_id", key_id))
+}
+
+fn execute_update_year_discounts(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    discounts: Vec<(u64, u64)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for (years, bps) in &discounts {
+        if *years == 0 || *bps > BPS_DENOMINATOR {
+            return Err(ContractError::InvalidArguments);
+        }
+        if *bps == 0 {
+            YEAR_DISCOUNTS.remove(deps.storage, *years);
+        } else {
+            YEAR_DISCOUNTS.save(deps.storage, *years, bps)?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_year_discounts")
+        .add_attribute(
+            "discounts",
+            discounts
+                .iter()
+                .map(|(years, bps)| format!("{}:{}", years, bps))
+                .collect::<Vec<String>>()
+                .join(","),
+        ))
+}
+
+fn execute_update_thresholds(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    thresholds: Vec<(u8, u32)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for (l, threshold) in &thresholds {
+        if *l == 0 {
+            return Err(ContractError::InvalidArguments);
+        }
+        if *threshold == 0 {
+            THRESHOLDS.remove(deps.storage, *l);
+        } else {
+            THRESHOLDS.save(deps.storage, *l, threshold)?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_thresholds")
+        .add_attribute(
+            "thresholds",
+            thresholds
+                .iter()
+                .map(|(l, threshold)| format!("{}:{}", l, threshold))
+                .collect::<Vec<String>>()
+                .join(","),
+        ))
 }
 
 fn execute_update_prices(
@@ -426,15 +1246,7 @@ fn execute_update_prices(
     }
 
     for (l, price) in &prices {
-        PRICE_INFO.update(deps.storage, *l, |d| -> StdResult<Coin> {
-            match d {
-                Some(coin) => Ok(coin),
-                None => Ok(Coin {
-                    denom: price.denom.clone(),
-                    amount: price.amount,
-                }),
-            }
-        })?;
+        PRICE_INFO.save(deps.storage, (*l, &price.denom), &price.amount)?;
     }
 
     Ok(Response::new()
@@ -443,12 +1255,96 @@ fn execute_update_prices(
             "prices",
             prices
                 .iter()
-                .map(|(l, price)| format!("{}:{}", l, price.to_string()))
+                .map(|(l, price)| format!("{}:{}", l, price))
+                .collect::<Vec<String>>()
+                .join(","),
+        ))
+}
+
+fn execute_remove_prices(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    prices: Vec<(u8, String)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for (l, denom) in &prices {
+        PRICE_INFO.remove(deps.storage, (*l, denom));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_prices")
+        .add_attribute(
+            "prices",
+            prices
+                .iter()
+                .map(|(l, denom)| format!("{}:{}", l, denom))
                 .collect::<Vec<String>>()
                 .join(","),
         ))
 }
 
+fn execute_update_cw20_prices(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    prices: Vec<(u8, Cw20Coin)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for (l, price) in &prices {
+        let token_addr = deps.api.addr_validate(&price.address)?;
+        CW20_PRICE_INFO.save(deps.storage, (*l, &token_addr), &price.amount)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_cw20_prices")
+        .add_attribute(
+            "prices",
+            prices
+                .iter()
+                .map(|(l, price)| format!("{}:{}{}", l, price.amount, price.address))
+                .collect::<Vec<String>>()
+                .join(","),
+        ))
+}
+
+fn execute_remove_cw20_prices(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    prices: Vec<(u8, String)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for (l, address) in &prices {
+        let token_addr = deps.api.addr_validate(address)?;
+        CW20_PRICE_INFO.remove(deps.storage, (*l, &token_addr));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_cw20_prices")
+        .add_attribute(
+            "prices",
+            prices
+                .iter()
+                .map(|(l, address)| format!("{}:{}", l, address))
+                .collect::<Vec<String>>()
+                .join(","),
+        ))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn execute_update_config(
     deps: DepsMut,
     _env: Env,
@@ -458,12 +1354,30 @@ fn execute_update_config(
     name_code_id: u64,
     resolver_code_id: u64,
     max_year_register: u64,
+    min_durations: u64,
+    grace_period: u64,
+    redemption_period: u64,
+    redemption_fee_bps: u64,
+    premium_start: Vec<Coin>,
+    premium_period: u64,
+    min_commitment_age: u64,
+    max_commitment_age: u64,
+    registration_mode: RegistrationMode,
+    price_floor_bps: u64,
+    burn_bps: u64,
 ) -> Result<Response, ContractError> {
     // only contract admin can update config
     let config = CONFIG.load(deps.storage)?;
     if config.admin != info.sender {
         return Err(ContractError::Unauthorized {});
     }
+    if burn_bps > BPS_DENOMINATOR
+        || min_durations == 0
+        || min_durations > year_to_secs(max_year_register)
+        || min_commitment_age > max_commitment_age
+    {
+        return Err(ContractError::InvalidArguments);
+    }
 
     // update config
     let new_config = Config {
@@ -472,6 +1386,17 @@ fn execute_update_config(
         name_code_id,
         resolver_code_id,
         max_year_register,
+        min_durations,
+        grace_period,
+        redemption_period,
+        redemption_fee_bps,
+        premium_start: premium_start.clone(),
+        premium_period,
+        min_commitment_age,
+        max_commitment_age,
+        registration_mode: registration_mode.clone(),
+        price_floor_bps,
+        burn_bps,
     };
     CONFIG.save(deps.storage, &new_config)?;
 
@@ -481,17 +1406,59 @@ fn execute_update_config(
         .add_attribute("operator", operator.to_string())
         .add_attribute("name_code_id", name_code_id.to_string())
         .add_attribute("resolver_code_id", resolver_code_id.to_string())
-        .add_attribute("max_year_register", max_year_register.to_string()))
+        .add_attribute("max_year_register", max_year_register.to_string())
+        .add_attribute("min_durations", min_durations.to_string())
+        .add_attribute("grace_period", grace_period.to_string())
+        .add_attribute("redemption_period", redemption_period.to_string())
+        .add_attribute("redemption_fee_bps", redemption_fee_bps.to_string())
+        .add_attribute("premium_start", coins_to_string(&premium_start))
+        .add_attribute("premium_period", premium_period.to_string())
+        .add_attribute("min_commitment_age", min_commitment_age.to_string())
+        .add_attribute("max_commitment_age", max_commitment_age.to_string())
+        .add_attribute("registration_mode", registration_mode.to_string())
+        .add_attribute("price_floor_bps", price_floor_bps.to_string())
+        .add_attribute("burn_bps", burn_bps.to_string()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::Verifier {} => to_binary(&query_verifier(deps)?),
+        QueryMsg::Verifier { key_id } => to_binary(&query_verifier(deps, key_id)?),
+        QueryMsg::Verifiers {} => to_binary(&query_verifiers(deps)?),
+        QueryMsg::Thresholds {} => to_binary(&query_thresholds(deps)?),
+        QueryMsg::YearDiscounts {} => to_binary(&query_year_discounts(deps)?),
         QueryMsg::Prices {} => to_binary(&query_prices(deps)?),
+        QueryMsg::Cw20Prices {} => to_binary(&query_cw20_prices(deps)?),
         QueryMsg::NameContract {} => to_binary(&query_name_contract(deps)?),
-        QueryMsg::HasRegister { name } => to_binary(&query_has_register(deps, name)?),
+        QueryMsg::HasRegister { name } => to_binary(&query_has_register(deps, env, name)?),
+        QueryMsg::RegistrationStatus { name } => {
+            to_binary(&query_registration_status(deps, env, name)?)
+        }
+        QueryMsg::Quote {
+            name,
+            durations,
+            denom,
+            sender,
+        } => to_binary(&query_quote(deps, env, name, durations, denom, sender)?),
+        QueryMsg::MakeCommitment {
+            name,
+            owner,
+            secret,
+        } => to_binary(&query_make_commitment(deps, name, owner, secret)?),
+        QueryMsg::Commitment { commitment } => to_binary(&query_commitment(deps, commitment)?),
+        QueryMsg::Nonce { address } => to_binary(&query_nonce(deps, address)?),
+        QueryMsg::RevenueShares {} => to_binary(&query_revenue_shares(deps)?),
+        QueryMsg::Revenues {} => to_binary(&query_revenues(deps)?),
+        QueryMsg::Burned {} => to_binary(&query_burned(deps)?),
+        QueryMsg::ReferralRates {} => to_binary(&query_referral_rates(deps)?),
+        QueryMsg::ReferralRewards { referrer } => {
+            to_binary(&query_referral_rewards(deps, referrer)?)
+        }
+        QueryMsg::Reserved { name } => to_binary(&query_reserved(deps, name)?),
+        QueryMsg::ReservedNames { start_after, limit } => {
+            to_binary(&query_reserved_names(deps, start_after, limit)?)
+        }
     }
 }
 
@@ -499,26 +1466,244 @@ fn query_config(deps: Deps) -> StdResult<Config> {
     CONFIG.load(deps.storage)
 }
 
-fn query_verifier(deps: Deps) -> StdResult<Verifier> {
-    VERIFIER.load(deps.storage)
+fn query_revenue_shares(deps: Deps) -> StdResult<Vec<RevenueShare>> {
+    Ok(REVENUE_SHARES.may_load(deps.storage)?.unwrap_or_default())
+}
+
+fn query_revenues(deps: Deps) -> StdResult<Vec<Revenue>> {
+    REVENUES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, revenue)| revenue))
+        .collect()
+}
+
+fn query_burned(deps: Deps) -> StdResult<Vec<Coin>> {
+    BURNED
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect()
+}
+
+fn query_referral_rates(deps: Deps) -> StdResult<Vec<(Addr, u64)>> {
+    REFERRAL_RATES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+fn query_referral_rewards(deps: Deps, referrer: String) -> StdResult<Vec<ReferralReward>> {
+    let referrer = deps.api.addr_validate(&referrer)?;
+    REFERRAL_REWARDS
+        .prefix(&referrer)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, reward)| reward))
+        .collect()
+}
+
+fn query_verifier(deps: Deps, key_id: String) -> StdResult<Verifier> {
+    VERIFIERS.load(deps.storage, &key_id)
+}
+
+fn query_year_discounts(deps: Deps) -> StdResult<Vec<(u64, u64)>> {
+    YEAR_DISCOUNTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+fn query_thresholds(deps: Deps) -> StdResult<Vec<(u8, u32)>> {
+    THRESHOLDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+fn query_verifiers(deps: Deps) -> StdResult<Vec<Verifier>> {
+    VERIFIERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, verifier)| verifier))
+        .collect()
 }
 
 fn query_prices(deps: Deps) -> StdResult<PricesResponse> {
     let prices_res: StdResult<Vec<_>> = PRICE_INFO
         .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|((l, denom), amount)| (l, Coin { denom, amount })))
         .collect();
     match prices_res {
-        Ok(prices) => Ok(PricesResponse { prices: prices }),
+        Ok(prices) => Ok(PricesResponse { prices }),
         Err(_) => Ok(PricesResponse { prices: vec![] }),
     }
 }
 
+fn query_cw20_prices(deps: Deps) -> StdResult<Cw20PricesResponse> {
+    let prices = CW20_PRICE_INFO
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|((l, address), amount)| {
+                (
+                    l,
+                    Cw20Coin {
+                        address: address.to_string(),
+                        amount,
+                    },
+                )
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(Cw20PricesResponse { prices })
+}
+
 fn query_name_contract(deps: Deps) -> StdResult<Addr> {
     NAME_CONTRACT.load(deps.storage)
 }
 
-fn query_has_register(deps: Deps, name: String) -> StdResult<bool> {
-    Ok(REGISTERS.has(deps.storage, &name))
+// Names out of grace and redemption periods are considered as not registered
+fn query_has_register(deps: Deps, env: Env, name: String) -> StdResult<bool> {
+    let res = query_registration_status(deps, env, name)?;
+    Ok(res.status != RegistrationStatus::Available)
+}
+
+fn query_registration_status(
+    deps: Deps,
+    env: Env,
+    name: String,
+) -> StdResult<RegistrationStatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let name = normalize_name(deps, &name)?;
+    let expires = REGISTERS.may_load(deps.storage, &name)?;
+    let status = match expires {
+        Some(expires) => registration_status(&config, expires, env.block.time.seconds()),
+        None => RegistrationStatus::Available,
+    };
+    Ok(RegistrationStatusResponse { status, expires })
+}
+
+// Quote the fee to register or extend a name the same way register and extend charge it
+fn query_quote(
+    deps: Deps,
+    env: Env,
+    name: String,
+    durations: u64,
+    denom: Denom,
+    sender: Option<String>,
+) -> StdResult<QuoteResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let name_contract = NAME_CONTRACT.load(deps.storage)?;
+    let name = query_name_policy(deps, &name_contract)?.normalize(&name);
+    let sender = sender
+        .map(|sender| deps.api.addr_validate(&sender))
+        .transpose()?;
+    let expires = REGISTERS.may_load(deps.storage, &name)?;
+    let quote = quote_fee(
+        deps,
+        &config,
+        &name,
+        durations,
+        &denom,
+        expires,
+        env.block.time.seconds(),
+        None,
+    )
+    .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    // Same rules as register and extend, owners renew without the backend
+    let signature_required = match &sender {
+        Some(sender) if *sender == config.admin => false,
+        _ if quote.status == RegistrationStatus::Available => {
+            config.registration_mode != RegistrationMode::Open
+        }
+        Some(sender) => {
+            let owner: cw721::OwnerOfResponse = deps.querier.query_wasm_smart(
+                &name_contract,
+                &aurans_name::QueryMsg::OwnerOf {
+                    token_id: name.clone(),
+                    include_expired: Some(true),
+                },
+            )?;
+            owner.owner != *sender
+                && config.registration_mode == RegistrationMode::SignatureRequired
+        }
+        None => config.registration_mode == RegistrationMode::SignatureRequired,
+    };
+
+    Ok(QuoteResponse {
+        denom,
+        tier: quote.tier,
+        status: quote.status,
+        price: quote.price,
+        discount: quote.discount,
+        premium: quote.premium,
+        redemption_fee: quote.redemption_fee,
+        total: quote.total,
+        accepted: check_durations(&config, durations).is_ok(),
+        signature_required,
+    })
+}
+
+fn query_make_commitment(
+    deps: Deps,
+    name: String,
+    owner: String,
+    secret: Binary,
+) -> StdResult<Binary> {
+    let name = normalize_name(deps, &name)?;
+    let owner = deps.api.addr_validate(&owner)?;
+    let commitment = commitment_hash(&name, owner.as_str(), &secret)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(Binary(commitment))
+}
+
+// Return the time the commitment is committed
+fn query_commitment(deps: Deps, commitment: Binary) -> StdResult<Option<u64>> {
+    commitments().may_load(deps.storage, &commitment)
+}
+
+fn query_nonce(deps: Deps, address: String) -> StdResult<u64> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(NONCES.may_load(deps.storage, &address)?.unwrap_or_default())
+}
+
+fn query_reserved(deps: Deps, name: String) -> StdResult<Option<ReservedName>> {
+    let name = normalize_name(deps, &name)?;
+    let claimant = RESERVED.may_load(deps.storage, &name)?;
+    Ok(claimant.map(|claimant| ReservedName { name, claimant }))
+}
+
+fn query_reserved_names(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReservedNamesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let names = RESERVED
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(name, claimant)| ReservedName { name, claimant }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ReservedNamesResponse { names })
+}
+
+fn query_name_policy(deps: Deps, name_contract: &Addr) -> StdResult<NamePolicy> {
+    deps.querier.query_wasm_smart(
+        name_contract,
+        &aurans_name::QueryMsg::Extension {
+            msg: aurans_name::NameQueryMsg::NamePolicy {},
+        },
+    )
+}
+
+// Normalize name by the policy of name contract, the same as register and extend
+fn normalize_name(deps: Deps, name: &str) -> StdResult<String> {
+    let name_contract = NAME_CONTRACT.load(deps.storage)?;
+    Ok(query_name_policy(deps, &name_contract)?.normalize(name))
+}
+
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|coin| coin.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
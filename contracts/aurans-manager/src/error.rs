@@ -21,6 +21,30 @@ pub enum ContractError {
     #[error("Invalid Signature")]
     InvalidSignature,
 
+    #[error("Verifier Not Found: {key_id:?}")]
+    VerifierNotFound { key_id: String },
+
+    #[error("Unsupported Algorithm: {algorithm:?}")]
+    UnsupportedAlgorithm { algorithm: String },
+
+    #[error("Verifier Not Active: {key_id:?}")]
+    VerifierNotActive { key_id: String },
+
+    #[error("Duplicate Signature: {key_id:?}")]
+    DuplicateSignature { key_id: String },
+
+    #[error("Threshold Not Met: {signed:?} of {threshold:?}")]
+    ThresholdNotMet { signed: u32, threshold: u32 },
+
+    #[error("Signature Expired")]
+    SignatureExpired,
+
+    #[error("Backend Signature Required")]
+    SignatureRequired,
+
+    #[error("Invalid Nonce, expected: {expected:?}")]
+    InvalidNonce { expected: u64 },
+
     #[error("Invalid Address")]
     InvalidAddress,
 
@@ -30,9 +54,27 @@ pub enum ContractError {
     #[error("Insufficient Funds")]
     InsufficientFunds,
 
+    #[error("Invalid Denom: {denom:?}")]
+    InvalidDenom { denom: String },
+
+    #[error("Price Below Floor, price: {price:?}, floor: {floor:?}")]
+    PriceBelowFloor { price: String, floor: String },
+
     #[error("Invalid Arguments")]
     InvalidArguments,
 
+    #[error("Batch Too Long")]
+    BatchTooLong,
+
+    #[error("Invalid Revenue Shares")]
+    InvalidRevenueShares,
+
+    #[error("No Referral Rewards")]
+    NoReferralRewards,
+
+    #[error("Withdraw Exceeds Available Balance: {available:?}")]
+    WithdrawExceedsBalance { available: String },
+
     #[error("Limit Year Register")]
     LimitYearRegister,
 
@@ -42,6 +84,30 @@ pub enum ContractError {
     #[error("Name Has Registered: {name:?}")]
     NameRegistered { name: String },
 
+    #[error("Name Reserved: {name:?}")]
+    NameReserved { name: String },
+
+    #[error("Invalid Name: {name:?}")]
+    InvalidName { name: String },
+
     #[error("Name Not Registered: {name:?}")]
     NameNotRegistered { name: String },
+
+    #[error("Name Expired: {name:?}")]
+    NameExpired { name: String },
+
+    #[error("Commitment Exists")]
+    CommitmentExists,
+
+    #[error("Commitment Not Found")]
+    CommitmentNotFound,
+
+    #[error("Commitment Too New")]
+    CommitmentTooNew,
+
+    #[error("Commitment Expired")]
+    CommitmentExpired,
+
+    #[error("Commitment Required")]
+    CommitmentRequired,
 }
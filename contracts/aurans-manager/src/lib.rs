@@ -1,7 +1,9 @@
+pub mod commit;
 pub mod contract;
 pub mod error;
 pub mod msg;
 pub mod price;
+pub mod revenue;
 pub mod state;
 pub mod util;
 pub mod verify;
@@ -1,8 +1,12 @@
+use std::fmt;
+
+use aurans_name::policy::NamePolicy;
 use aurans_name::state::Metadata;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Binary, Coin};
+use cosmwasm_std::{Addr, Binary, Coin, Uint128};
+use cw20::{Cw20Coin, Cw20ReceiveMsg, Denom};
 
-use crate::state::{Config, Verifier};
+use crate::state::{Config, ReferralReward, RegistrationMode, Revenue, RevenueShare, Verifier};
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
@@ -10,14 +14,28 @@ pub struct InstantiateMsg {
     pub admin: String,
     pub operator: String,
     pub prices: Vec<(u8, Coin)>,
-    pub backend_pubkey: Binary,
+    pub verifiers: Vec<Verifier>,
     pub name_code_id: u64,
     pub resolver_code_id: u64,
     pub max_year_register: u64,
+    pub min_durations: u64,
+    pub grace_period: u64,
+    pub redemption_period: u64,
+    pub redemption_fee_bps: u64,
+    pub premium_start: Vec<Coin>,
+    pub premium_period: u64,
+    pub min_commitment_age: u64,
+    pub max_commitment_age: u64,
+    pub registration_mode: RegistrationMode,
+    pub price_floor_bps: u64,
+    pub burn_bps: u64,
+    // Validation policy of names, passed to the name contract
+    pub name_policy: Option<NamePolicy>,
 }
 
 /// Message type for `execute` entry_point
 #[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     UpdateConfig {
         admin: String,
@@ -25,32 +43,135 @@ pub enum ExecuteMsg {
         name_code_id: u64,
         resolver_code_id: u64,
         max_year_register: u64,
+        min_durations: u64,
+        grace_period: u64,
+        redemption_period: u64,
+        redemption_fee_bps: u64,
+        premium_start: Vec<Coin>,
+        premium_period: u64,
+        min_commitment_age: u64,
+        max_commitment_age: u64,
+        registration_mode: RegistrationMode,
+        price_floor_bps: u64,
+        burn_bps: u64,
     },
     UpdatePrices {
         prices: Vec<(u8, Coin)>,
     },
+    RemovePrices {
+        prices: Vec<(u8, String)>,
+    },
+    UpdateCw20Prices {
+        prices: Vec<(u8, Cw20Coin)>,
+    },
+    RemoveCw20Prices {
+        prices: Vec<(u8, String)>,
+    },
+    // Add a verifier key or update the key with same key id
     UpdateVerifier {
-        backend_pubkey: Binary,
+        verifier: Verifier,
+    },
+    RemoveVerifier {
+        key_id: String,
+    },
+    // Discount in basis points of registering or extending for at least the years,
+    // zero basis points removes the discount
+    UpdateYearDiscounts {
+        discounts: Vec<(u64, u64)>,
+    },
+    // Number of backend signatures required to register or extend names of length in chars,
+    // zero removes the threshold
+    UpdateThresholds {
+        thresholds: Vec<(u8, u32)>,
+    },
+    // Reserve names with an optional claimant, reserving again updates the claimant
+    ReserveNames {
+        names: Vec<(String, Option<String>)>,
+    },
+    UnreserveNames {
+        names: Vec<String>,
+    },
+    Commit {
+        commitment: Binary,
     },
     Register {
         name: String,
-        backend_signature: Binary,
+        backend_signature: Option<BackendSignature>,
         metadata: Metadata,
+        // Reveal of the commitment, the name is minted to the committed owner. Non admin
+        // senders must reveal a commitment when the min commitment age is not zero
+        secret: Option<Binary>,
+        owner: Option<String>,
+        // Partner earning a commission of the fee, see `UpdateReferralRates`
+        referrer: Option<String>,
+    },
+    // Register up to 10 names paid by the funds sent once, each item is checked
+    // like `Register` and signed with the next nonce of sender
+    RegisterBatch {
+        items: Vec<RegisterItem>,
+        referrer: Option<String>,
     },
     Extend {
         name: String,
-        backend_signature: Binary,
+        backend_signature: Option<BackendSignature>,
         durations: u64,
+        referrer: Option<String>,
+    },
+    // Extend up to 10 names with their durations paid by the funds sent once, without
+    // backend signatures the sender must be the owner unless registration is open for renewals
+    ExtendBatch {
+        names: Vec<(String, u64)>,
+        referrer: Option<String>,
     },
     Unregister {
         names: Vec<String>,
     },
     Withdraw {
         receiver: String,
-        coin: Coin,
+        coin: WithdrawCoin,
+    },
+    // Recipients of fees with their basis points, the basis points must sum to 10000
+    UpdateRevenueShares {
+        shares: Vec<(String, u64)>,
+    },
+    // Split the balance of each fee denom to revenue share recipients, anyone can call it
+    Distribute {},
+    // Commission rates of referrers in basis points of the fee, zero rate removes the referrer
+    UpdateReferralRates {
+        rates: Vec<(String, u64)>,
+    },
+    // Send the unclaimed commissions of sender in every denom
+    ClaimReferralRewards {},
+    // Pay register or extend with a whitelisted cw20 token
+    Receive(Cw20ReceiveMsg),
+}
+
+/// Message type of cw20 `Send` to the manager
+#[cw_serde]
+#[allow(clippy::large_enum_variant)]
+pub enum ReceiveMsg {
+    Register {
+        name: String,
+        backend_signature: Option<BackendSignature>,
+        metadata: Metadata,
+        secret: Option<Binary>,
+        owner: Option<String>,
+        referrer: Option<String>,
+    },
+    Extend {
+        name: String,
+        backend_signature: Option<BackendSignature>,
+        durations: u64,
+        referrer: Option<String>,
     },
 }
 
+#[cw_serde]
+pub enum WithdrawCoin {
+    Native(Coin),
+    Cw20(Cw20Coin),
+}
+
 /// Message type for `migrate` entry_point
 #[cw_serde]
 pub enum MigrateMsg {}
@@ -62,13 +183,63 @@ pub enum QueryMsg {
     #[returns(Config)]
     Config {},
     #[returns(Verifier)]
-    Verifier {},
+    Verifier { key_id: String },
+    #[returns(Vec<Verifier>)]
+    Verifiers {},
+    #[returns(Vec<(u8, u32)>)]
+    Thresholds {},
+    #[returns(Vec<(u64, u64)>)]
+    YearDiscounts {},
     #[returns(PricesResponse)]
     Prices {},
+    #[returns(Cw20PricesResponse)]
+    Cw20Prices {},
     #[returns(Addr)]
     NameContract {},
     #[returns(bool)]
     HasRegister { name: String },
+    #[returns(RegistrationStatusResponse)]
+    RegistrationStatus { name: String },
+    #[returns(QuoteResponse)]
+    // Fee to register the name, or to extend it if it is not available, as charged
+    // to sender without a signed price
+    Quote {
+        name: String,
+        durations: u64,
+        denom: Denom,
+        sender: Option<String>,
+    },
+    #[returns(Binary)]
+    MakeCommitment {
+        name: String,
+        owner: String,
+        secret: Binary,
+    },
+    #[returns(Option<u64>)]
+    Commitment { commitment: Binary },
+    // Nonce expected in the next backend signature of address
+    #[returns(u64)]
+    Nonce { address: String },
+    #[returns(Vec<RevenueShare>)]
+    RevenueShares {},
+    // Fees collected and distributed per denom
+    #[returns(Vec<Revenue>)]
+    Revenues {},
+    // Total fees burned per denom, cw20 tokens use their contract address as denom
+    #[returns(Vec<Coin>)]
+    Burned {},
+    #[returns(Vec<(Addr, u64)>)]
+    ReferralRates {},
+    // Commissions accrued and claimed by referrer per denom
+    #[returns(Vec<ReferralReward>)]
+    ReferralRewards { referrer: String },
+    #[returns(Option<ReservedName>)]
+    Reserved { name: String },
+    #[returns(ReservedNamesResponse)]
+    ReservedNames {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -76,19 +247,135 @@ pub struct PricesResponse {
     pub prices: Vec<(u8, Coin)>,
 }
 
+#[cw_serde]
+pub struct Cw20PricesResponse {
+    pub prices: Vec<(u8, Cw20Coin)>,
+}
+
+#[cw_serde]
+pub enum RegistrationStatus {
+    // Name is registered and resolving
+    Active,
+    // Name is expired, owner can still extend it at normal price
+    Grace,
+    // Name is expired, owner can reclaim it with a penalty fee
+    Redemption,
+    // Name is not registered or released, anyone can register it
+    Available,
+}
+
+impl fmt::Display for RegistrationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistrationStatus::Active => write!(f, "active"),
+            RegistrationStatus::Grace => write!(f, "grace"),
+            RegistrationStatus::Redemption => write!(f, "redemption"),
+            RegistrationStatus::Available => write!(f, "available"),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct QuoteResponse {
+    pub denom: Denom,
+    // Price tier applied, the char length of name or 0 for the base price
+    pub tier: u8,
+    // Names not available are quoted to extend
+    pub status: RegistrationStatus,
+    // Price of the years before discounts
+    pub price: Uint128,
+    // Multi-year discount, or the discount of signed price
+    pub discount: Uint128,
+    pub premium: Uint128,
+    pub redemption_fee: Uint128,
+    // Fee charged
+    pub total: Uint128,
+    // Whether the durations are accepted by min durations and max year register
+    pub accepted: bool,
+    // Whether sender needs backend signatures, an unknown sender if not given
+    pub signature_required: bool,
+}
+
+#[cw_serde]
+pub struct RegistrationStatusResponse {
+    pub status: RegistrationStatus,
+    pub expires: Option<u64>,
+}
+
+#[cw_serde]
+pub struct ReservedName {
+    pub name: String,
+    pub claimant: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct ReservedNamesResponse {
+    pub names: Vec<ReservedName>,
+}
+
+#[cw_serde]
+pub struct CommitmentMsg {
+    pub name: String,
+    pub owner: String,
+    pub secret: Binary,
+}
+
+#[cw_serde]
+pub struct BackendSignature {
+    // Signatures of distinct verifier keys, at least the threshold of the price tier
+    pub signatures: Vec<KeySignature>,
+    // Must equal the nonce of sender, see `QueryMsg::Nonce`
+    pub nonce: u64,
+    // Block time in seconds the signature can be used until
+    pub valid_until: u64,
+    // Price signed by backend for promotions, charged instead of the table price
+    // down to the price floor, cw20 tokens use their contract address as denom
+    pub price: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct RegisterItem {
+    pub name: String,
+    pub backend_signature: Option<BackendSignature>,
+    // Durations to register are `metadata.durations`
+    pub metadata: Metadata,
+    pub secret: Option<Binary>,
+    pub owner: Option<String>,
+}
+
+#[cw_serde]
+pub struct KeySignature {
+    // Id of the verifier key signed the message
+    pub key_id: String,
+    pub signature: Binary,
+}
+
+// Payload signed by backend, `contract` is the manager address so a signature
+// is only valid on one deployment
 #[cw_serde]
 pub enum VerifyMsg {
     Register {
         name: String,
         sender: String,
         chain_id: String,
+        contract: String,
         bech32_prefixes: Vec<String>,
         durations: u64,
+        nonce: u64,
+        valid_until: u64,
+        // Not serialized when empty, so messages without price are signed as before
+        #[serde(skip_serializing_if = "Option::is_none")]
+        price: Option<Coin>,
     },
     Extend {
         name: String,
         sender: String,
         chain_id: String,
+        contract: String,
         durations: u64,
+        nonce: u64,
+        valid_until: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        price: Option<Coin>,
     },
 }
@@ -1,29 +1,357 @@
-use std::ops::Mul;
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Coin, CosmosMsg, Deps, Order, StdResult, Uint128, Uint256, WasmMsg,
+};
+use cw20::{Cw20CoinVerified, Cw20ExecuteMsg, Denom};
 
-use cosmwasm_std::{Coin, Deps, Uint128};
+use crate::{
+    error::ContractError,
+    msg::RegistrationStatus,
+    state::{Config, CW20_PRICE_INFO, PRICE_INFO, YEAR_DISCOUNTS},
+    util::{
+        name_length, registration_status, release_time, sec_to_years, year_to_secs,
+        BPS_DENOMINATOR, SEC_PER_YEAR,
+    },
+};
 
-use crate::{error::ContractError, state::PRICE_INFO};
+// Fee of register or extend, `price` is before the multi-year and signed price
+// discounts and `total` is charged
+pub struct Quote {
+    pub tier: u8,
+    pub status: RegistrationStatus,
+    pub price: Uint128,
+    pub discount: Uint128,
+    pub premium: Uint128,
+    pub redemption_fee: Uint128,
+    pub total: Uint128,
+}
+
+// Funds paid for register or extend, native coins sent with the message
+// or cw20 tokens received by the `Receive` hook
+pub enum Payment {
+    Native(Vec<Coin>),
+    Cw20(Cw20CoinVerified),
+}
 
-pub fn calc_price(deps: Deps, name: &str, years: &u64) -> Result<Coin, ContractError> {
-    let name_len = name.len() as u8;
-    let has_price = PRICE_INFO.has(deps.storage, name_len);
-    if !has_price {
-        // Base price
-        let price = PRICE_INFO.load(deps.storage, 0)?;
-        return Ok(price);
+// Key of denom in config coins and errors, cw20 tokens are keyed by contract address
+pub fn denom_key(denom: &Denom) -> &str {
+    match denom {
+        Denom::Native(denom) => denom,
+        Denom::Cw20(address) => address.as_str(),
     }
-    let mut price = PRICE_INFO.load(deps.storage, name_len)?;
-    price.amount = price.amount.mul(Uint128::from(*years));
-    Ok(price)
 }
 
-pub fn check_fee(price: Coin, funds: &Vec<Coin>) -> Result<(), ContractError> {
-    if funds
-        .into_iter()
-        .any(|fund| fund.denom == price.denom && fund.amount >= price.amount)
-    {
-        Ok(())
+pub fn amount_to_string(amount: Uint128, denom: &Denom) -> String {
+    format!("{}{}", amount, denom_key(denom))
+}
+
+// Price tier of name is the char length of name if it has prices configured,
+// otherwise the base tier 0
+pub fn price_tier(deps: Deps, name: &str) -> u8 {
+    let name_len = name_length(name);
+    let has_price = PRICE_INFO
+        .prefix(name_len)
+        .range(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some()
+        || CW20_PRICE_INFO
+            .prefix(name_len)
+            .range(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+    if has_price {
+        name_len
     } else {
-        Err(ContractError::InsufficientFunds)
+        0
+    }
+}
+
+// Pick the first denom of payment accepted by the price tier of name
+pub fn payment_denom(deps: Deps, name: &str, payment: &Payment) -> Result<Denom, ContractError> {
+    let tier = price_tier(deps, name);
+    match payment {
+        Payment::Native(funds) => funds
+            .iter()
+            .find(|fund| PRICE_INFO.has(deps.storage, (tier, &fund.denom)))
+            .map(|fund| Denom::Native(fund.denom.clone()))
+            .ok_or(ContractError::InsufficientFunds),
+        Payment::Cw20(coin) => {
+            if !CW20_PRICE_INFO.has(deps.storage, (tier, &coin.address)) {
+                return Err(ContractError::InvalidDenom {
+                    denom: coin.address.to_string(),
+                });
+            }
+            Ok(Denom::Cw20(coin.address.clone()))
+        }
+    }
+}
+
+// Price of durations pro rata to the unit price of one year, rounded up, and its
+// discount by the basis points of the longest discount term in whole years not
+// longer than durations
+pub fn durations_price(
+    unit_price: Uint128,
+    durations: u64,
+    discounts: &[(u64, u64)],
+) -> (Uint128, Uint128) {
+    // Round up so a part of the unit price is never free
+    let mut price = unit_price.multiply_ratio(durations, SEC_PER_YEAR);
+    if !(unit_price.full_mul(durations) % Uint256::from(SEC_PER_YEAR)).is_zero() {
+        price += Uint128::one();
+    }
+    let years = sec_to_years(durations);
+    let discount_bps = discounts
+        .iter()
+        .filter(|(term, _)| *term <= years)
+        .max_by_key(|(term, _)| *term)
+        .map(|(_, bps)| *bps)
+        .unwrap_or_default();
+    (price, price.multiply_ratio(discount_bps, BPS_DENOMINATOR))
+}
+
+// Price of name for durations and its multi-year discount, every tier is priced per year
+pub fn calc_price(
+    deps: Deps,
+    name: &str,
+    durations: u64,
+    denom: &Denom,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let tier = price_tier(deps, name);
+    let amount = match denom {
+        Denom::Native(denom) => PRICE_INFO.may_load(deps.storage, (tier, denom))?,
+        Denom::Cw20(address) => CW20_PRICE_INFO.may_load(deps.storage, (tier, address))?,
+    }
+    .ok_or_else(|| ContractError::InvalidDenom {
+        denom: denom_key(denom).to_owned(),
+    })?;
+    let discounts = YEAR_DISCOUNTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(durations_price(amount, durations, &discounts))
+}
+
+// Durations must be at least the min durations and at most the max year register
+pub fn check_durations(config: &Config, durations: u64) -> Result<(), ContractError> {
+    if durations < config.min_durations {
+        return Err(ContractError::InvalidDurations);
+    }
+    if durations > year_to_secs(config.max_year_register) {
+        return Err(ContractError::LimitYearRegister);
+    }
+    Ok(())
+}
+
+// Fee of name for durations, shared by register, extend and the quote query. A name with
+// expires is extended unless it is available again. Backend signed price replaces
+// the table price, recently released names are charged a temporary premium and
+// reclaim in redemption period is charged a penalty fee
+#[allow(clippy::too_many_arguments)]
+pub fn quote_fee(
+    deps: Deps,
+    config: &Config,
+    name: &str,
+    durations: u64,
+    denom: &Denom,
+    expires: Option<u64>,
+    now: u64,
+    signed_price: Option<&Coin>,
+) -> Result<Quote, ContractError> {
+    let status = match expires {
+        Some(expires) => registration_status(config, expires, now),
+        None => RegistrationStatus::Available,
+    };
+    let (price, year_discount) = calc_price(deps, name, durations, denom)?;
+    let table_price = price - year_discount;
+    let charged = match signed_price {
+        Some(signed_price) => check_signed_price(config, signed_price, table_price, denom)?,
+        None => table_price,
+    };
+    let premium = match status {
+        RegistrationStatus::Available => calc_premium(config, expires, now, denom)?,
+        _ => Uint128::zero(),
+    };
+    let redemption_fee = match status {
+        RegistrationStatus::Redemption => {
+            charged.multiply_ratio(config.redemption_fee_bps, BPS_DENOMINATOR)
+        }
+        _ => Uint128::zero(),
+    };
+    Ok(Quote {
+        tier: price_tier(deps, name),
+        status,
+        price,
+        discount: price.saturating_sub(charged),
+        premium,
+        redemption_fee,
+        total: charged + premium + redemption_fee,
+    })
+}
+
+// Backend signed price must be in the payment denom and not lower than
+// the price floor of the table price
+pub fn check_signed_price(
+    config: &Config,
+    price: &Coin,
+    table_price: Uint128,
+    denom: &Denom,
+) -> Result<Uint128, ContractError> {
+    if price.denom != denom_key(denom) {
+        return Err(ContractError::InvalidDenom {
+            denom: price.denom.clone(),
+        });
+    }
+    let floor = table_price.multiply_ratio(config.price_floor_bps, BPS_DENOMINATOR);
+    if price.amount < floor {
+        return Err(ContractError::PriceBelowFloor {
+            price: price.to_string(),
+            floor: amount_to_string(floor, denom),
+        });
+    }
+    Ok(price.amount)
+}
+
+// Temporary premium of a released name, it decays linearly from the premium start
+// to zero over the premium period after the name is released
+pub fn calc_premium(
+    config: &Config,
+    expires: Option<u64>,
+    now: u64,
+    denom: &Denom,
+) -> Result<Uint128, ContractError> {
+    let released = match expires {
+        Some(expires) => release_time(config, expires),
+        None => return Ok(Uint128::zero()),
+    };
+    let premium_ends = released + config.premium_period;
+    if now < released || now >= premium_ends {
+        return Ok(Uint128::zero());
+    }
+    // Denoms without premium start can not pay until the premium is over
+    let denom = denom_key(denom);
+    let premium_start = config
+        .premium_start
+        .iter()
+        .find(|premium| premium.denom == denom)
+        .ok_or_else(|| ContractError::InvalidDenom {
+            denom: denom.to_owned(),
+        })?;
+    Ok(premium_start
+        .amount
+        .multiply_ratio(premium_ends - now, config.premium_period))
+}
+
+// Charge price from payment, return the change
+pub fn check_fee(
+    price: Uint128,
+    denom: &Denom,
+    payment: Payment,
+) -> Result<Payment, ContractError> {
+    match (denom, payment) {
+        (Denom::Native(denom), Payment::Native(funds)) => {
+            let mut charged = false;
+            let mut change = Vec::new();
+            for mut fund in funds {
+                if !charged && fund.denom == *denom {
+                    if fund.amount < price {
+                        return Err(ContractError::InsufficientFunds);
+                    }
+                    fund.amount -= price;
+                    charged = true;
+                }
+                if !fund.amount.is_zero() {
+                    change.push(fund);
+                }
+            }
+            if !charged {
+                return Err(ContractError::InsufficientFunds);
+            }
+            Ok(Payment::Native(change))
+        }
+        (Denom::Cw20(address), Payment::Cw20(coin)) if *address == coin.address => {
+            if coin.amount < price {
+                return Err(ContractError::InsufficientFunds);
+            }
+            Ok(Payment::Cw20(Cw20CoinVerified {
+                address: coin.address,
+                amount: coin.amount - price,
+            }))
+        }
+        _ => Err(ContractError::InsufficientFunds),
+    }
+}
+
+// Message to return the change to sender, empty if there is no change
+pub fn refund_msgs(sender: &Addr, change: Payment) -> StdResult<Vec<CosmosMsg>> {
+    match change {
+        Payment::Native(coins) => {
+            if coins.is_empty() {
+                return Ok(vec![]);
+            }
+            Ok(vec![BankMsg::Send {
+                to_address: sender.to_string(),
+                amount: coins,
+            }
+            .into()])
+        }
+        Payment::Cw20(coin) => {
+            if coin.amount.is_zero() {
+                return Ok(vec![]);
+            }
+            Ok(vec![WasmMsg::Execute {
+                contract_addr: coin.address.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: sender.to_string(),
+                    amount: coin.amount,
+                })?,
+                funds: vec![],
+            }
+            .into()])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Uint128;
+
+    use super::durations_price;
+    use crate::util::{year_to_secs, SEC_PER_YEAR};
+
+    // (unit price, durations, discounts, price, discount)
+    type Case<'a> = (u128, u64, &'a [(u64, u64)], u128, u128);
+
+    #[test]
+    fn test_durations_price() {
+        let discounts = [(3, 500), (5, 1000)];
+        let cases: [Case; 16] = [
+            (100, year_to_secs(1), &[], 100, 0),
+            (100, year_to_secs(5), &[], 500, 0),
+            (100, year_to_secs(1), &discounts, 100, 0),
+            (100, year_to_secs(2), &discounts, 200, 0),
+            (100, year_to_secs(3), &discounts, 300, 15),
+            (100, year_to_secs(4), &discounts, 400, 20),
+            (100, year_to_secs(5), &discounts, 500, 50),
+            (100, year_to_secs(10), &discounts, 1000, 100),
+            // Discount is rounded down
+            (333, year_to_secs(3), &discounts, 999, 49),
+            // Part of a year is priced pro rata
+            (100, SEC_PER_YEAR / 2, &[], 50, 0),
+            (365, 86_400, &[], 1, 0),
+            // Part of a unit is rounded up, it is never free
+            (1, 1, &[], 1, 0),
+            (100, SEC_PER_YEAR / 3, &[], 34, 0),
+            (100, year_to_secs(1) + SEC_PER_YEAR * 9 / 10, &[], 190, 0),
+            // Discount term is counted in whole years
+            (100, year_to_secs(3) - 1, &discounts, 300, 0),
+            (100, year_to_secs(3) + SEC_PER_YEAR / 2, &discounts, 350, 17),
+        ];
+        for (unit_price, durations, discounts, price, discount) in cases {
+            assert_eq!(
+                durations_price(Uint128::from(unit_price), durations, discounts),
+                (Uint128::from(price), Uint128::from(discount)),
+                "{} for {} seconds",
+                unit_price,
+                durations
+            );
+        }
     }
 }
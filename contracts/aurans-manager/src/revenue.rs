@@ -0,0 +1,184 @@
+use cosmwasm_std::{
+    to_binary, Addr, Api, BankMsg, Coin, CosmosMsg, Deps, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Denom};
+
+use crate::{
+    price::denom_key,
+    state::{
+        Config, ReferralReward, Revenue, RevenueShare, BURNED, REFERRAL_RATES, REFERRAL_REWARDS,
+        REVENUES, UNCLAIMED_REFERRAL_REWARDS,
+    },
+    util::BPS_DENOMINATOR,
+};
+
+// Balance of denom held by contract except the commissions not claimed yet,
+// which belong to referrers
+pub fn available_balance(deps: Deps, contract: &Addr, denom: &Denom) -> StdResult<Uint128> {
+    let balance = match denom {
+        Denom::Native(denom) => deps.querier.query_balance(contract, denom)?.amount,
+        Denom::Cw20(address) => {
+            let res: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+                address,
+                &cw20::Cw20QueryMsg::Balance {
+                    address: contract.to_string(),
+                },
+            )?;
+            res.balance
+        }
+    };
+    let unclaimed = UNCLAIMED_REFERRAL_REWARDS
+        .may_load(deps.storage, denom_key(denom))?
+        .unwrap_or_default();
+    Ok(balance.saturating_sub(unclaimed))
+}
+
+// Add fee charged by register or extend to the accumulated revenue of denom
+pub fn record_revenue(storage: &mut dyn Storage, denom: &Denom, fee: Uint128) -> StdResult<()> {
+    if fee.is_zero() {
+        return Ok(());
+    }
+    REVENUES.update(storage, denom_key(denom), |revenue| -> StdResult<_> {
+        let mut revenue = revenue.unwrap_or(Revenue {
+            denom: denom.clone(),
+            accumulated: Uint128::zero(),
+            distributed: Uint128::zero(),
+        });
+        revenue.accumulated += fee;
+        Ok(revenue)
+    })?;
+    Ok(())
+}
+
+// Burn amount of fee and add it to the burned total of denom
+pub fn burn_fee(
+    storage: &mut dyn Storage,
+    denom: &Denom,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    if amount.is_zero() {
+        return Ok(vec![]);
+    }
+    BURNED.update(storage, denom_key(denom), |burned| -> StdResult<_> {
+        Ok(burned.unwrap_or_default() + amount)
+    })?;
+    let burn_msg = match denom {
+        Denom::Native(denom) => BankMsg::Burn {
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount,
+            }],
+        }
+        .into(),
+        Denom::Cw20(address) => WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+    Ok(vec![burn_msg])
+}
+
+// Referrer and its commission of a fee
+pub type Referral = (Addr, Uint128);
+
+// Settle fee charged by register or extend: burn share of the fee, referrer earns
+// a commission of the rest and the remaining is revenue. Return the burn messages,
+// the burned amount and the referrer with its commission, senders can not refer themselves
+pub fn settle_fee(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    config: &Config,
+    denom: &Denom,
+    fee: Uint128,
+    referrer: Option<String>,
+    sender: &Addr,
+) -> StdResult<(Vec<CosmosMsg>, Uint128, Option<Referral>)> {
+    let burned = fee.multiply_ratio(config.burn_bps, BPS_DENOMINATOR);
+    let burn_msgs = burn_fee(storage, denom, burned)?;
+    let referral = match referrer {
+        Some(referrer) => Some(api.addr_validate(&referrer)?),
+        None => None,
+    }
+    .filter(|referrer| referrer != sender)
+    .map(|referrer| -> StdResult<_> {
+        let commission = accrue_referral(storage, &referrer, denom, fee - burned)?;
+        Ok((referrer, commission))
+    })
+    .transpose()?;
+    let commission = referral
+        .as_ref()
+        .map(|(_, commission)| *commission)
+        .unwrap_or_default();
+    record_revenue(storage, denom, fee - burned - commission)?;
+    Ok((burn_msgs, burned, referral))
+}
+
+// Accrue the commission of referrer on fee, return zero if referrer has no commission rate
+pub fn accrue_referral(
+    storage: &mut dyn Storage,
+    referrer: &Addr,
+    denom: &Denom,
+    fee: Uint128,
+) -> StdResult<Uint128> {
+    let rate = REFERRAL_RATES
+        .may_load(storage, referrer)?
+        .unwrap_or_default();
+    let commission = fee.multiply_ratio(rate, BPS_DENOMINATOR);
+    if commission.is_zero() {
+        return Ok(commission);
+    }
+    let key = denom_key(denom);
+    REFERRAL_REWARDS.update(storage, (referrer, key), |reward| -> StdResult<_> {
+        let mut reward = reward.unwrap_or(ReferralReward {
+            denom: denom.clone(),
+            accrued: Uint128::zero(),
+            claimed: Uint128::zero(),
+        });
+        reward.accrued += commission;
+        Ok(reward)
+    })?;
+    UNCLAIMED_REFERRAL_REWARDS.update(storage, key, |unclaimed| -> StdResult<_> {
+        Ok(unclaimed.unwrap_or_default() + commission)
+    })?;
+    Ok(commission)
+}
+
+// Split amount by the basis points of shares, the last recipient takes the rounding remainder
+pub fn split_revenue(shares: &[RevenueShare], amount: Uint128) -> Vec<(Addr, Uint128)> {
+    let mut remaining = amount;
+    let mut splits = Vec::new();
+    for (i, share) in shares.iter().enumerate() {
+        let part = if i == shares.len() - 1 {
+            remaining
+        } else {
+            amount.multiply_ratio(share.bps, BPS_DENOMINATOR)
+        };
+        remaining -= part;
+        splits.push((share.recipient.clone(), part));
+    }
+    splits
+}
+
+pub fn transfer_msg(denom: &Denom, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(match denom {
+        Denom::Native(denom) => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount,
+            }],
+        }
+        .into(),
+        Denom::Cw20(address) => WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    })
+}
@@ -1,6 +1,9 @@
+use std::fmt;
+
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Binary, Coin};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Binary, Coin, Uint128};
+use cw20::Denom;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 #[cw_serde]
 pub struct Config {
@@ -11,18 +14,172 @@ pub struct Config {
     pub resolver_code_id: u64,
 
     pub max_year_register: u64,
+    // Shortest durations in seconds of register and extend, durations are priced pro rata
+    pub min_durations: u64,
+
+    // Seconds after expiry the owner can still extend the name at normal price
+    pub grace_period: u64,
+    // Seconds after grace period the owner can reclaim the name with a penalty fee
+    pub redemption_period: u64,
+    // Penalty fee in basis points of the extend price
+    pub redemption_fee_bps: u64,
+
+    // Temporary premium charged when a name is released, one coin per accepted denom,
+    // cw20 tokens use their contract address as denom
+    pub premium_start: Vec<Coin>,
+    // Seconds the premium takes to decay to zero after the name is released
+    pub premium_period: u64,
+
+    // Seconds a commitment must wait before it can be revealed
+    pub min_commitment_age: u64,
+    // Seconds a commitment can be revealed before it is stale
+    pub max_commitment_age: u64,
+
+    // Whether register and extend of non admin senders require backend signatures
+    pub registration_mode: RegistrationMode,
+
+    // Lowest backend signed price in basis points of the table price
+    pub price_floor_bps: u64,
+
+    // Share of every fee burned in basis points
+    pub burn_bps: u64,
+}
+
+#[cw_serde]
+pub enum RegistrationMode {
+    // Register and extend require backend signatures
+    SignatureRequired,
+    // Backend signatures are optional, names are only checked on chain
+    Open,
+    // Only extend is open, register requires backend signatures
+    OpenForRenewals,
+}
+
+impl fmt::Display for RegistrationMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistrationMode::SignatureRequired => write!(f, "signature_required"),
+            RegistrationMode::Open => write!(f, "open"),
+            RegistrationMode::OpenForRenewals => write!(f, "open_for_renewals"),
+        }
+    }
+}
+
+#[cw_serde]
+pub enum KeyAlgorithm {
+    Secp256k1,
+    Ed25519,
+    // Not supported by the current VM, keys are rejected until it is
+    Secp256r1,
+}
+
+impl fmt::Display for KeyAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyAlgorithm::Secp256k1 => write!(f, "secp256k1"),
+            KeyAlgorithm::Ed25519 => write!(f, "ed25519"),
+            KeyAlgorithm::Secp256r1 => write!(f, "secp256r1"),
+        }
+    }
+}
+
+#[cw_serde]
+pub enum SignScheme {
+    // Signature over sha256 of the json `VerifyMsg`
+    Raw,
+    // ADR-036 `signArbitrary` amino sign doc of the json `VerifyMsg` by signer address,
+    // so a Keplr or Ledger key can be the verifier
+    Adr036 { signer: String },
 }
 
 #[cw_serde]
 pub struct Verifier {
+    pub key_id: String,
     pub backend_pubkey: Binary,
+    pub algorithm: KeyAlgorithm,
+    pub scheme: SignScheme,
+    // Block time in seconds the key is valid from
+    pub not_before: u64,
+    // Block time in seconds the key is valid until, none if it never expires
+    pub not_after: Option<u64>,
+}
+
+#[cw_serde]
+pub struct RevenueShare {
+    pub recipient: Addr,
+    pub bps: u64,
+}
+
+#[cw_serde]
+pub struct Revenue {
+    pub denom: Denom,
+    // Total fees collected by register and extend
+    pub accumulated: Uint128,
+    // Total fees paid to revenue share recipients
+    pub distributed: Uint128,
+}
+
+#[cw_serde]
+pub struct ReferralReward {
+    pub denom: Denom,
+    pub accrued: Uint128,
+    pub claimed: Uint128,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
-// PRICE_INFO has keys are length of name.
-// The value of key is zero meaning other length of name not in config
-pub const PRICE_INFO: Map<u8, Coin> = Map::new("price_info");
-pub const VERIFIER: Item<Verifier> = Item::new("verify");
+// PRICE_INFO has keys are length of name and accepted denom.
+// The length of name is zero meaning other length of name not in config
+pub const PRICE_INFO: Map<(u8, &str), Uint128> = Map::new("price_info");
+// CW20_PRICE_INFO has keys are length of name and accepted cw20 token address,
+// same as PRICE_INFO the length of name is zero meaning the base price
+pub const CW20_PRICE_INFO: Map<(u8, &Addr), Uint128> = Map::new("cw20_price_info");
+// A map key id to backend verifier key, keys overlap during rotation
+pub const VERIFIERS: Map<&str, Verifier> = Map::new("verifiers");
+// A map name length in chars to the number of backend signatures required, default is 1
+pub const THRESHOLDS: Map<u8, u32> = Map::new("thresholds");
+// A map years to the discount in basis points of registering or extending at least the years
+pub const YEAR_DISCOUNTS: Map<u64, u64> = Map::new("year_discounts");
+// A map sender to the nonce expected in its next backend signature
+pub const NONCES: Map<&Addr, u64> = Map::new("nonces");
 pub const NAME_CONTRACT: Item<Addr> = Item::new("name_contract");
 // A map name registed with expires (seconds)
 pub const REGISTERS: Map<&str, u64> = Map::new("registers");
+// A map reserved name to its designated claimant, reserved names can only be
+// registered by the claimant or admin
+pub const RESERVED: Map<&str, Option<Addr>> = Map::new("reserved");
+// Recipients of the fees split by `Distribute`, basis points sum to 10000
+pub const REVENUE_SHARES: Item<Vec<RevenueShare>> = Item::new("revenue_shares");
+// A map denom to fees collected and distributed, cw20 tokens are keyed by contract address
+pub const REVENUES: Map<&str, Revenue> = Map::new("revenues");
+// A map referrer to its commission rate in basis points of the fee
+pub const REFERRAL_RATES: Map<&Addr, u64> = Map::new("referral_rates");
+// A map (referrer, denom) to commissions accrued and claimed by referrer
+pub const REFERRAL_REWARDS: Map<(&Addr, &str), ReferralReward> = Map::new("referral_rewards");
+// A map denom to commissions not claimed yet, they are kept out of `Distribute`
+pub const UNCLAIMED_REFERRAL_REWARDS: Map<&str, Uint128> = Map::new("unclaimed_referral_rewards");
+// A map denom to the total fees burned
+pub const BURNED: Map<&str, Uint128> = Map::new("burned");
+
+pub struct CommitmentIndexes<'a> {
+    pub committed_at: MultiIndex<'a, u64, u64, Vec<u8>>,
+}
+
+impl<'a> IndexList<u64> for CommitmentIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<u64>> + '_> {
+        let v: Vec<&dyn Index<u64>> = vec![&self.committed_at];
+        Box::new(v.into_iter())
+    }
+}
+
+// A IndexedMap map commitment hash to the time it is committed (seconds),
+// indexed by the time to prune stale commitments
+pub fn commitments<'a>() -> IndexedMap<'a, &'a [u8], u64, CommitmentIndexes<'a>> {
+    let indexes = CommitmentIndexes {
+        committed_at: MultiIndex::new(
+            |_pk, committed_at: &u64| *committed_at,
+            "commitments",
+            "commitments__committed_at",
+        ),
+    };
+    IndexedMap::new("commitments", indexes)
+}
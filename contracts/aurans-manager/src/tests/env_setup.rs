@@ -1,5 +1,6 @@
 #[cfg(test)]
 pub mod env {
+    use cosmrs::{bip32, crypto::secp256k1::SigningKey};
     use cosmwasm_std::{Addr, Binary, Coin, Empty, Uint128};
     use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
 
@@ -18,9 +19,10 @@ pub mod env {
     };
 
     use crate::msg::InstantiateMsg as ManagerInstantiateMsg;
+    use crate::state::{KeyAlgorithm, RegistrationMode, SignScheme, Verifier};
 
-    pub const ADMIN: &str = "aura1000000000000000000000000000000000admin";
-    // pub const USER_1: &str = "aura1000000000000000000000000000000000user1";
+    pub const ADMIN: &str = "aura1yntfxtwysmgjp6wzza590xctjpzne3ak9scynv";
+    pub const USER_1: &str = "aura1qcjgq3vqpgrjvmk2z9pcrv67f89ecayhfe0feq";
 
     pub const NATIVE_DENOM: &str = "uaura";
     pub const NATIVE_BALANCE: u128 = 1_000_000_000_000u128;
@@ -30,11 +32,46 @@ pub mod env {
 
     pub const BASE_PRICE: u128 = 100_000u128;
 
+    // 30 days
+    pub const GRACE_PERIOD: u64 = 2_592_000;
+    pub const REDEMPTION_PERIOD: u64 = 2_592_000;
+    pub const REDEMPTION_FEE_BPS: u64 = 5_000;
+    pub const PREMIUM_START: u128 = 1_000_000u128;
+    // 10 days
+    pub const PREMIUM_PERIOD: u64 = 864_000;
+    pub const MIN_COMMITMENT_AGE: u64 = 60;
+    // 1 day
+    pub const MAX_COMMITMENT_AGE: u64 = 86_400;
+    pub const PRICE_FLOOR_BPS: u64 = 5_000;
+    // 30 days
+    pub const MIN_DURATIONS: u64 = 2_592_000;
+
+    // Mnemonic of the backend key signing register and extend messages
+    pub const BACKEND_MNEMONIC: &str = "notice oak worry limit wrap speak medal online prefer cluster roof addict wrist behave treat actual wasp year salad speed social layer crew genius";
+
+    pub const BACKEND_KEY_ID: &str = "backend-1";
+
     pub struct ContractInfo {
         pub contract_addr: String,
+        // Not read by the tests yet
+        #[allow(dead_code)]
         pub contract_code_id: u64,
     }
 
+    // Signing key of backend mnemonic at account index
+    pub fn signer_at(index: u32) -> SigningKey {
+        let seed = bip32::Mnemonic::new(BACKEND_MNEMONIC, bip32::Language::English)
+            .unwrap()
+            .to_seed("");
+        let derivation_path = format!("m/44'/118'/0'/0/{}", index);
+        let xprv = bip32::XPrv::derive_from_path(seed, &derivation_path.parse().unwrap()).unwrap();
+        xprv.into()
+    }
+
+    pub fn backend_signer() -> SigningKey {
+        signer_at(0)
+    }
+
     // create app instance and init balance of NATIVE token for admin
     fn mock_app() -> App {
         AppBuilder::new().build(|router, _, storage| {
@@ -55,6 +92,17 @@ pub mod env {
                     ],
                 )
                 .unwrap();
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &Addr::unchecked(USER_1),
+                    vec![Coin {
+                        denom: NATIVE_DENOM.to_string(),
+                        amount: Uint128::new(NATIVE_BALANCE),
+                    }],
+                )
+                .unwrap();
         })
     }
 
@@ -78,6 +126,16 @@ pub mod env {
         Box::new(contract)
     }
 
+    // create cw20 token contract to pay with
+    pub fn cw20_contract_template() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        );
+        Box::new(contract)
+    }
+
     pub fn instantiate_contracts() -> (App, Vec<ContractInfo>) {
         // Create a new app instance
         let mut app = mock_app();
@@ -141,10 +199,33 @@ pub mod env {
                             },
                         ),
                     ],
-                    backend_pubkey: Binary::from(b"test"),
+                    verifiers: vec![Verifier {
+                        key_id: BACKEND_KEY_ID.to_owned(),
+                        backend_pubkey: Binary(backend_signer().public_key().to_bytes()),
+                        algorithm: KeyAlgorithm::Secp256k1,
+                        scheme: SignScheme::Raw,
+                        not_before: 0,
+                        not_after: None,
+                    }],
                     name_code_id: name_contract_code_id,
                     resolver_code_id: resolver_contract_code_id,
                     max_year_register: 5,
+                    min_durations: MIN_DURATIONS,
+                    grace_period: GRACE_PERIOD,
+                    redemption_period: REDEMPTION_PERIOD,
+                    redemption_fee_bps: REDEMPTION_FEE_BPS,
+                    premium_start: vec![Coin {
+                        denom: NATIVE_DENOM.to_string(),
+                        amount: Uint128::from(PREMIUM_START),
+                    }],
+                    premium_period: PREMIUM_PERIOD,
+                    // Commitments are only required by the commitment tests
+                    min_commitment_age: 0,
+                    max_commitment_age: MAX_COMMITMENT_AGE,
+                    registration_mode: RegistrationMode::SignatureRequired,
+                    price_floor_bps: PRICE_FLOOR_BPS,
+                    burn_bps: 0,
+                    name_policy: None,
                 },
                 &[],
                 "test instantiate contract",
@@ -1,5 +1,2935 @@
 #![cfg(test)]
 mod tests {
+    use aurans_name::policy::{Charset, NamePolicy};
+    use aurans_name::state::Metadata;
+    use aurans_resolver::msg::AddressResponse;
+    use cosmrs::crypto::secp256k1::SigningKey;
+    use cosmwasm_std::{coin, to_binary, Addr, Binary, Coin, Event, Uint128};
+    use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Denom};
+    use cw_multi_test::{App, Executor};
+
+    use crate::error::ContractError;
+    use crate::msg::{
+        BackendSignature, Cw20PricesResponse, ExecuteMsg, KeySignature, PricesResponse, QueryMsg,
+        QuoteResponse, ReceiveMsg, RegistrationStatus, RegistrationStatusResponse, ReservedName,
+        ReservedNamesResponse, VerifyMsg, WithdrawCoin,
+    };
+    use crate::state::{Config, KeyAlgorithm, RegistrationMode, SignScheme, Verifier};
+    use crate::tests::env_setup::env::{
+        backend_signer, cw20_contract_template, instantiate_contracts, signer_at, ADMIN,
+        BACKEND_KEY_ID, BASE_PRICE, GRACE_PERIOD, MAX_COMMITMENT_AGE, MIN_COMMITMENT_AGE,
+        MIN_DURATIONS, NATIVE_BALANCE, NATIVE_BALANCE_2, NATIVE_DENOM, NATIVE_DENOM_2,
+        PREMIUM_PERIOD, PREMIUM_START, REDEMPTION_PERIOD, USER_1,
+    };
+    use crate::util::year_to_secs;
 
     mod install_testing {}
+
+    fn name_contract(app: &App, manager: &str) -> Addr {
+        app.wrap()
+            .query_wasm_smart(manager, &QueryMsg::NameContract {})
+            .unwrap()
+    }
+
+    fn resolver_contract(app: &App, manager: &str) -> Addr {
+        let name_contract = name_contract(app, manager);
+        let resolver: aurans_name::state::Resolver = app
+            .wrap()
+            .query_wasm_smart(
+                name_contract,
+                &aurans_name::QueryMsg::Extension {
+                    msg: aurans_name::NameQueryMsg::Resolver {},
+                },
+            )
+            .unwrap();
+        resolver.address
+    }
+
+    fn register_msg(name: &str, durations: u64) -> ExecuteMsg {
+        ExecuteMsg::Register {
+            name: name.to_owned(),
+            backend_signature: None,
+            metadata: Metadata {
+                bech32_prefixes: vec!["aura".to_owned()],
+                durations,
+                ..Metadata::default()
+            },
+            secret: None,
+            owner: None,
+            referrer: None,
+        }
+    }
+
+    fn extend_msg(name: &str, durations: u64) -> ExecuteMsg {
+        ExecuteMsg::Extend {
+            name: name.to_owned(),
+            backend_signature: None,
+            durations,
+            referrer: None,
+        }
+    }
+
+    fn registration_status(app: &App, manager: &str, name: &str) -> RegistrationStatus {
+        let res: RegistrationStatusResponse = app
+            .wrap()
+            .query_wasm_smart(
+                manager,
+                &QueryMsg::RegistrationStatus {
+                    name: name.to_owned(),
+                },
+            )
+            .unwrap();
+        res.status
+    }
+
+    fn sign(signer: &SigningKey, verify_msg: &VerifyMsg) -> Binary {
+        let signature = signer
+            .sign(serde_json_wasm::to_string(verify_msg).unwrap().as_bytes())
+            .unwrap();
+        Binary(signature.to_vec())
+    }
+
+    // Register message signed by backend for a non admin sender
+    fn signed_register_msg(
+        app: &App,
+        manager: &Addr,
+        sender: &str,
+        name: &str,
+        nonce: u64,
+    ) -> ExecuteMsg {
+        signed_register_msg_with_key(
+            app,
+            manager,
+            sender,
+            name,
+            nonce,
+            &[(BACKEND_KEY_ID, &backend_signer())],
+        )
+    }
+
+    fn signed_register_msg_with_key(
+        app: &App,
+        manager: &Addr,
+        sender: &str,
+        name: &str,
+        nonce: u64,
+        keys: &[(&str, &SigningKey)],
+    ) -> ExecuteMsg {
+        let verify_msg = register_verify_msg(app, manager, sender, name, nonce);
+        let signatures = keys
+            .iter()
+            .map(|(key_id, signer)| KeySignature {
+                key_id: (*key_id).to_owned(),
+                signature: sign(signer, &verify_msg),
+            })
+            .collect();
+        register_msg_with_signatures(&verify_msg, signatures)
+    }
+
+    // Payload backend signs to register name for one year
+    fn register_verify_msg(
+        app: &App,
+        manager: &Addr,
+        sender: &str,
+        name: &str,
+        nonce: u64,
+    ) -> VerifyMsg {
+        VerifyMsg::Register {
+            name: name.to_owned(),
+            sender: sender.to_owned(),
+            chain_id: app.block_info().chain_id,
+            contract: manager.to_string(),
+            bech32_prefixes: vec!["aura".to_owned()],
+            durations: year_to_secs(1),
+            nonce,
+            valid_until: app.block_info().time.seconds() + 600,
+            price: None,
+        }
+    }
+
+    fn register_msg_with_signatures(
+        verify_msg: &VerifyMsg,
+        signatures: Vec<KeySignature>,
+    ) -> ExecuteMsg {
+        let (name, durations, nonce, valid_until, price) = match verify_msg {
+            VerifyMsg::Register {
+                name,
+                durations,
+                nonce,
+                valid_until,
+                price,
+                ..
+            } => (name, *durations, *nonce, *valid_until, price.clone()),
+            _ => unreachable!(),
+        };
+        match register_msg(name, durations) {
+            ExecuteMsg::Register {
+                name,
+                metadata,
+                secret,
+                owner,
+                referrer,
+                ..
+            } => ExecuteMsg::Register {
+                name,
+                backend_signature: Some(BackendSignature {
+                    signatures,
+                    nonce,
+                    valid_until,
+                    price,
+                }),
+                metadata,
+                secret,
+                owner,
+                referrer,
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn update_config(app: &mut App, manager: &Addr, update: impl FnOnce(&mut Config)) {
+        let msg = update_config_msg(app, manager, update);
+        app.execute_contract(Addr::unchecked(ADMIN), manager.clone(), &msg, &[])
+            .unwrap();
+    }
+
+    fn update_config_msg(
+        app: &App,
+        manager: &Addr,
+        update: impl FnOnce(&mut Config),
+    ) -> ExecuteMsg {
+        let mut config: Config = app
+            .wrap()
+            .query_wasm_smart(manager, &QueryMsg::Config {})
+            .unwrap();
+        update(&mut config);
+        ExecuteMsg::UpdateConfig {
+            admin: config.admin.to_string(),
+            operator: config.operator.to_string(),
+            name_code_id: config.name_code_id,
+            resolver_code_id: config.resolver_code_id,
+            max_year_register: config.max_year_register,
+            min_durations: config.min_durations,
+            grace_period: config.grace_period,
+            redemption_period: config.redemption_period,
+            redemption_fee_bps: config.redemption_fee_bps,
+            premium_start: config.premium_start,
+            premium_period: config.premium_period,
+            min_commitment_age: config.min_commitment_age,
+            max_commitment_age: config.max_commitment_age,
+            registration_mode: config.registration_mode,
+            price_floor_bps: config.price_floor_bps,
+            burn_bps: config.burn_bps,
+        }
+    }
+
+    fn update_name_policy(app: &mut App, manager: &Addr, name_policy: NamePolicy) {
+        let name_contract = name_contract(app, manager.as_str());
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            name_contract,
+            &aurans_name::ExecuteMsg::Extension {
+                msg: aurans_name::NameExecuteMsg::UpdateNamePolicy { name_policy },
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    // Error of a failed execute, overpayment is refunded so tests can send more fee
+    // than needed
+    fn execute_err(
+        app: &mut App,
+        sender: &str,
+        manager: &Addr,
+        msg: &ExecuteMsg,
+        funds: &[Coin],
+    ) -> String {
+        app.execute_contract(Addr::unchecked(sender), manager.clone(), msg, funds)
+            .unwrap_err()
+            .downcast::<ContractError>()
+            .unwrap()
+            .to_string()
+    }
+
+    fn base_fee(amount: u128) -> Vec<Coin> {
+        vec![Coin {
+            denom: NATIVE_DENOM.to_owned(),
+            amount: Uint128::from(amount),
+        }]
+    }
+
+    mod expiry_testing {
+        use super::*;
+
+        #[test]
+        fn names_page_scans_at_most_limit_records() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = contracts[0].contract_addr.clone();
+            let admin = Addr::unchecked(ADMIN);
+            let resolver = resolver_contract(&app, &manager);
+            let names = |app: &App, start_after: Option<String>| {
+                let res: aurans_resolver::msg::NamesResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        &resolver,
+                        &aurans_resolver::QueryMsg::Names {
+                            owner: ADMIN.to_owned(),
+                            start_after,
+                            limit: Some(1),
+                        },
+                    )
+                    .unwrap();
+                res
+            };
+
+            app.execute_contract(
+                admin.clone(),
+                Addr::unchecked(&manager),
+                &register_msg("tiennv", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(year_to_secs(1));
+            });
+            app.execute_contract(
+                admin,
+                Addr::unchecked(&manager),
+                &register_msg("tiennw", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            // Page of the expired record is empty but not the last page
+            let page = names(&app, None);
+            assert!(page.names.is_empty());
+            assert!(page.next_start_after.is_some());
+            let page = names(&app, page.next_start_after);
+            assert_eq!(page.names, vec!["tiennw.aura".to_owned()]);
+            let page = names(&app, page.next_start_after);
+            assert!(page.names.is_empty());
+            assert_eq!(page.next_start_after, None);
+        }
+
+        #[test]
+        fn expired_name_stops_resolving_and_can_register_again() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = contracts[0].contract_addr.clone();
+            let admin = Addr::unchecked(ADMIN);
+
+            app.execute_contract(
+                admin.clone(),
+                Addr::unchecked(&manager),
+                &register_msg("tiennv", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            let resolver = resolver_contract(&app, &manager);
+            let address_of = aurans_resolver::QueryMsg::AddressOf {
+                primary_name: "tiennv".to_owned(),
+                bech32_prefix: "aura".to_owned(),
+            };
+            let res: AddressResponse = app.wrap().query_wasm_smart(&resolver, &address_of).unwrap();
+            assert_eq!(res.address, ADMIN);
+            let all_addresses_of = aurans_resolver::QueryMsg::AllAddressesOf {
+                primary_name: "tiennv".to_owned(),
+                start_after: None,
+                limit: None,
+            };
+            let res: Vec<AddressResponse> = app
+                .wrap()
+                .query_wasm_smart(&resolver, &all_addresses_of)
+                .unwrap();
+            assert_eq!(res.len(), 1);
+
+            // Registering an active name is rejected
+            let name_registered = ContractError::NameRegistered {
+                name: "tiennv".to_owned(),
+            }
+            .to_string();
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    ADMIN,
+                    &Addr::unchecked(&manager),
+                    &register_msg("tiennv", year_to_secs(1)),
+                    &base_fee(BASE_PRICE),
+                ),
+                name_registered
+            );
+
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(year_to_secs(1));
+            });
+
+            // Name does not resolve anymore
+            let err = app
+                .wrap()
+                .query_wasm_smart::<AddressResponse>(&resolver, &address_of)
+                .unwrap_err();
+            assert!(err.to_string().contains("name expired"));
+            let res: Vec<AddressResponse> = app
+                .wrap()
+                .query_wasm_smart(&resolver, &all_addresses_of)
+                .unwrap();
+            assert!(res.is_empty());
+            let names: aurans_resolver::msg::NamesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &resolver,
+                    &aurans_resolver::QueryMsg::Names {
+                        owner: ADMIN.to_owned(),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert!(names.names.is_empty());
+            assert_eq!(
+                registration_status(&app, &manager, "tiennv"),
+                RegistrationStatus::Grace
+            );
+
+            // Expired name can not be transferred
+            let name_contract = name_contract(&app, &manager);
+            app.execute_contract(
+                admin.clone(),
+                name_contract,
+                &aurans_name::ExecuteMsg::TransferNft {
+                    recipient: USER_1.to_owned(),
+                    token_id: "tiennv".to_owned(),
+                },
+                &[],
+            )
+            .unwrap_err();
+
+            // Expired name is kept for the owner until grace and redemption periods are over
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    ADMIN,
+                    &Addr::unchecked(&manager),
+                    &register_msg("tiennv", year_to_secs(1)),
+                    &base_fee(BASE_PRICE),
+                ),
+                name_registered
+            );
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(GRACE_PERIOD + REDEMPTION_PERIOD);
+            });
+            let has_register: bool = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::HasRegister {
+                        name: "tiennv".to_owned(),
+                    },
+                )
+                .unwrap();
+            assert!(!has_register);
+
+            // Released name is charged a premium decaying over the premium period
+            let quote: QuoteResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::Quote {
+                        name: "tiennv".to_owned(),
+                        durations: year_to_secs(1),
+                        denom: Denom::Native(NATIVE_DENOM.to_owned()),
+                        sender: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(quote.premium, Uint128::from(PREMIUM_START));
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(PREMIUM_PERIOD / 2);
+            });
+            let quote: QuoteResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::Quote {
+                        name: "tiennv".to_owned(),
+                        durations: year_to_secs(1),
+                        denom: Denom::Native(NATIVE_DENOM.to_owned()),
+                        sender: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(quote.premium, Uint128::from(PREMIUM_START / 2));
+            assert_eq!(quote.total, Uint128::from(BASE_PRICE + PREMIUM_START / 2));
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    ADMIN,
+                    &Addr::unchecked(&manager),
+                    &register_msg("tiennv", year_to_secs(1)),
+                    &base_fee(BASE_PRICE),
+                ),
+                ContractError::InsufficientFunds.to_string()
+            );
+
+            // Released name can be registered again
+            app.execute_contract(
+                admin,
+                Addr::unchecked(&manager),
+                &register_msg("tiennv", year_to_secs(1)),
+                &base_fee(BASE_PRICE + PREMIUM_START / 2),
+            )
+            .unwrap();
+            let res: AddressResponse = app.wrap().query_wasm_smart(&resolver, &address_of).unwrap();
+            assert_eq!(res.address, ADMIN);
+        }
+
+        #[test]
+        fn owner_extends_in_grace_and_redemption_periods() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let owner = Addr::unchecked(USER_1);
+            let other = Addr::unchecked(
+                signer_at(3)
+                    .public_key()
+                    .account_id("aura")
+                    .unwrap()
+                    .to_string(),
+            );
+            app.send_tokens(
+                Addr::unchecked(ADMIN),
+                other.clone(),
+                &base_fee(BASE_PRICE * 4),
+            )
+            .unwrap();
+
+            app.execute_contract(
+                owner.clone(),
+                manager.clone(),
+                &signed_register_msg(&app, &manager, USER_1, "tiennv", 0),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            // Extend signed by backend for a sender who does not own the name
+            let signed_extend_msg = |app: &App, nonce: u64| {
+                let verify_msg = VerifyMsg::Extend {
+                    name: "tiennv".to_owned(),
+                    sender: other.to_string(),
+                    chain_id: app.block_info().chain_id,
+                    contract: manager.to_string(),
+                    durations: year_to_secs(1),
+                    nonce,
+                    valid_until: app.block_info().time.seconds() + 600,
+                    price: None,
+                };
+                ExecuteMsg::Extend {
+                    name: "tiennv".to_owned(),
+                    backend_signature: Some(BackendSignature {
+                        signatures: vec![KeySignature {
+                            key_id: BACKEND_KEY_ID.to_owned(),
+                            signature: sign(&backend_signer(), &verify_msg),
+                        }],
+                        nonce,
+                        valid_until: app.block_info().time.seconds() + 600,
+                        price: None,
+                    }),
+                    durations: year_to_secs(1),
+                    referrer: None,
+                }
+            };
+            let balance = |app: &App| {
+                app.wrap()
+                    .query_balance(USER_1, NATIVE_DENOM)
+                    .unwrap()
+                    .amount
+                    .u128()
+            };
+
+            // Grace period, only owner can extend at normal price
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(year_to_secs(1));
+            });
+            assert_eq!(
+                registration_status(&app, manager.as_str(), "tiennv"),
+                RegistrationStatus::Grace
+            );
+            let msg = signed_extend_msg(&app, 0);
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    other.as_str(),
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE),
+                ),
+                ContractError::Unauthorized {}.to_string()
+            );
+            let before = balance(&app);
+            app.execute_contract(
+                owner.clone(),
+                manager.clone(),
+                &extend_msg("tiennv", year_to_secs(1)),
+                &base_fee(BASE_PRICE * 2),
+            )
+            .unwrap();
+            assert_eq!(before - balance(&app), BASE_PRICE);
+            assert_eq!(
+                registration_status(&app, manager.as_str(), "tiennv"),
+                RegistrationStatus::Active
+            );
+
+            // Redemption period, only owner can reclaim with the penalty fee
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(year_to_secs(1) + GRACE_PERIOD);
+            });
+            assert_eq!(
+                registration_status(&app, manager.as_str(), "tiennv"),
+                RegistrationStatus::Redemption
+            );
+            let msg = signed_extend_msg(&app, 0);
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    other.as_str(),
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 2),
+                ),
+                ContractError::Unauthorized {}.to_string()
+            );
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &extend_msg("tiennv", year_to_secs(1)),
+                    &base_fee(BASE_PRICE),
+                ),
+                ContractError::InsufficientFunds.to_string()
+            );
+            let before = balance(&app);
+            app.execute_contract(
+                owner,
+                manager.clone(),
+                &extend_msg("tiennv", year_to_secs(1)),
+                &base_fee(BASE_PRICE * 2),
+            )
+            .unwrap();
+            assert_eq!(before - balance(&app), BASE_PRICE * 3 / 2);
+            assert_eq!(
+                registration_status(&app, manager.as_str(), "tiennv"),
+                RegistrationStatus::Active
+            );
+        }
+    }
+
+    mod commitment_testing {
+        use super::*;
+
+        fn make_commitment(app: &App, manager: &Addr, name: &str, owner: &str) -> Binary {
+            app.wrap()
+                .query_wasm_smart(
+                    manager,
+                    &QueryMsg::MakeCommitment {
+                        name: name.to_owned(),
+                        owner: owner.to_owned(),
+                        secret: Binary::from(b"secret"),
+                    },
+                )
+                .unwrap()
+        }
+
+        fn require_commitments(app: &mut App, manager: &Addr) {
+            update_config(app, manager, |config| {
+                config.min_commitment_age = MIN_COMMITMENT_AGE
+            });
+        }
+
+        fn reveal_msg(name: &str, owner: &str) -> ExecuteMsg {
+            match register_msg(name, year_to_secs(1)) {
+                ExecuteMsg::Register {
+                    name,
+                    backend_signature,
+                    metadata,
+                    ..
+                } => ExecuteMsg::Register {
+                    name,
+                    backend_signature,
+                    metadata,
+                    secret: Some(Binary::from(b"secret")),
+                    owner: Some(owner.to_owned()),
+                    referrer: None,
+                },
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn register_reveals_commitment_for_owner() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            require_commitments(&mut app, &manager);
+
+            // Commitment must be made before it is revealed
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    ADMIN,
+                    &manager,
+                    &reveal_msg("tiennv", USER_1),
+                    &base_fee(BASE_PRICE)
+                ),
+                ContractError::CommitmentNotFound.to_string()
+            );
+
+            let commitment = make_commitment(&app, &manager, "tiennv", USER_1);
+            // Names are normalized the same as register
+            assert_eq!(
+                make_commitment(&app, &manager, "TienNV", USER_1),
+                commitment
+            );
+            app.execute_contract(
+                Addr::unchecked(USER_1),
+                manager.clone(),
+                &ExecuteMsg::Commit {
+                    commitment: commitment.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+
+            // Commitment must wait the min commitment age
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    ADMIN,
+                    &manager,
+                    &reveal_msg("tiennv", USER_1),
+                    &base_fee(BASE_PRICE)
+                ),
+                ContractError::CommitmentTooNew.to_string()
+            );
+
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(MIN_COMMITMENT_AGE);
+            });
+            // Revealing with another owner does not match the commitment
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    ADMIN,
+                    &manager,
+                    &reveal_msg("tiennv", ADMIN),
+                    &base_fee(BASE_PRICE)
+                ),
+                ContractError::CommitmentNotFound.to_string()
+            );
+            app.execute_contract(
+                admin,
+                manager.clone(),
+                &reveal_msg("tiennv", USER_1),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            let resolver = resolver_contract(&app, manager.as_str());
+            let res: AddressResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &resolver,
+                    &aurans_resolver::QueryMsg::AddressOf {
+                        primary_name: "tiennv".to_owned(),
+                        bech32_prefix: "aura".to_owned(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.address, USER_1);
+
+            // Commitment is consumed
+            let committed_at: Option<u64> = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Commitment { commitment })
+                .unwrap();
+            assert_eq!(committed_at, None);
+        }
+
+        #[test]
+        fn min_commitment_age_must_not_exceed_max() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+
+            let msg = update_config_msg(&app, &manager, |config| {
+                config.min_commitment_age = MAX_COMMITMENT_AGE + 1
+            });
+            assert_eq!(
+                execute_err(&mut app, ADMIN, &manager, &msg, &base_fee(BASE_PRICE)),
+                ContractError::InvalidArguments.to_string()
+            );
+        }
+
+        #[test]
+        fn stale_commitments_are_pruned() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let user = Addr::unchecked(USER_1);
+
+            let stale = make_commitment(&app, &manager, "tiennv", USER_1);
+            app.execute_contract(
+                user.clone(),
+                manager.clone(),
+                &ExecuteMsg::Commit {
+                    commitment: stale.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+            // Unexpired commitment can not be committed again
+            let err = app
+                .execute_contract(
+                    user.clone(),
+                    manager.clone(),
+                    &ExecuteMsg::Commit {
+                        commitment: stale.clone(),
+                    },
+                    &[],
+                )
+                .unwrap_err()
+                .downcast::<ContractError>()
+                .unwrap();
+            assert_eq!(err.to_string(), ContractError::CommitmentExists.to_string());
+
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(MAX_COMMITMENT_AGE + 1);
+            });
+            app.execute_contract(
+                user,
+                manager.clone(),
+                &ExecuteMsg::Commit {
+                    commitment: make_commitment(&app, &manager, "aurans", USER_1),
+                },
+                &[],
+            )
+            .unwrap();
+
+            let committed_at: Option<u64> = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Commitment { commitment: stale })
+                .unwrap();
+            assert_eq!(committed_at, None);
+        }
+
+        #[test]
+        fn non_admin_register_requires_commitment() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            require_commitments(&mut app, &manager);
+
+            // Signed register without a commitment could front-run a reveal
+            let msg = signed_register_msg(&app, &manager, USER_1, "tiennv", 0);
+            assert_eq!(
+                execute_err(&mut app, USER_1, &manager, &msg, &base_fee(BASE_PRICE)),
+                ContractError::CommitmentRequired.to_string()
+            );
+            update_config(&mut app, &manager, |config| {
+                config.registration_mode = RegistrationMode::Open
+            });
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &register_msg("tiennv", year_to_secs(1)),
+                    &base_fee(BASE_PRICE)
+                ),
+                ContractError::CommitmentRequired.to_string()
+            );
+
+            // Owner is only accepted with the secret of its commitment
+            let msg = match reveal_msg("tiennv", USER_1) {
+                ExecuteMsg::Register {
+                    name,
+                    backend_signature,
+                    metadata,
+                    owner,
+                    referrer,
+                    ..
+                } => ExecuteMsg::Register {
+                    name,
+                    backend_signature,
+                    metadata,
+                    secret: None,
+                    owner,
+                    referrer,
+                },
+                _ => unreachable!(),
+            };
+            assert_eq!(
+                execute_err(&mut app, ADMIN, &manager, &msg, &base_fee(BASE_PRICE)),
+                ContractError::CommitmentRequired.to_string()
+            );
+
+            // Admin registers without commitments
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                manager.clone(),
+                &register_msg("tiennv", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+        }
+    }
+
+    mod fee_testing {
+        use super::*;
+
+        fn quote(
+            app: &App,
+            manager: &Addr,
+            name: &str,
+            years: u64,
+            sender: Option<&str>,
+        ) -> QuoteResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    manager,
+                    &QueryMsg::Quote {
+                        name: name.to_owned(),
+                        durations: year_to_secs(years),
+                        denom: Denom::Native(NATIVE_DENOM.to_owned()),
+                        sender: sender.map(|sender| sender.to_owned()),
+                    },
+                )
+                .unwrap()
+        }
+
+        #[test]
+        fn quote_matches_register_and_extend() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+
+            let res = quote(&app, &manager, "TienNV", 1, None);
+            assert_eq!(res.tier, 0);
+            assert_eq!(res.status, RegistrationStatus::Available);
+            assert_eq!(res.total, Uint128::from(BASE_PRICE));
+            assert!(res.accepted);
+            assert!(res.signature_required);
+            assert!(!quote(&app, &manager, "tiennv", 6, None).accepted);
+            assert!(!quote(&app, &manager, "tiennv", 1, Some(ADMIN)).signature_required);
+
+            let res = quote(&app, &manager, "aura", 1, None);
+            assert_eq!(res.tier, 4);
+            assert_eq!(res.total, Uint128::from(BASE_PRICE * 10));
+
+            // Registered name is quoted to extend, owner does not need backend signature
+            let msg = signed_register_msg(&app, &manager, USER_1, "tiennv", 0);
+            let fee = quote(&app, &manager, "tiennv", 1, Some(USER_1)).total;
+            app.execute_contract(
+                Addr::unchecked(USER_1),
+                manager.clone(),
+                &msg,
+                &base_fee(fee.u128()),
+            )
+            .unwrap();
+            let res = quote(&app, &manager, "tiennv", 1, Some(USER_1));
+            assert_eq!(res.status, RegistrationStatus::Active);
+            assert!(!res.signature_required);
+            assert!(quote(&app, &manager, "tiennv", 1, None).signature_required);
+            app.execute_contract(
+                Addr::unchecked(USER_1),
+                manager.clone(),
+                &extend_msg("tiennv", year_to_secs(1)),
+                &base_fee(res.total.u128()),
+            )
+            .unwrap();
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, fee + res.total);
+        }
+
+        #[test]
+        fn overpayment_is_refunded() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+
+            let funds = vec![
+                Coin {
+                    denom: NATIVE_DENOM.to_owned(),
+                    amount: Uint128::from(BASE_PRICE * 3),
+                },
+                Coin {
+                    denom: NATIVE_DENOM_2.to_owned(),
+                    amount: Uint128::from(1_000u128),
+                },
+            ];
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("tiennv", year_to_secs(1)),
+                &funds,
+            )
+            .unwrap();
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &extend_msg("tiennv", year_to_secs(1)),
+                &funds,
+            )
+            .unwrap();
+
+            let balance = app.wrap().query_balance(&admin, NATIVE_DENOM).unwrap();
+            assert_eq!(
+                balance.amount,
+                Uint128::from(NATIVE_BALANCE - BASE_PRICE * 2)
+            );
+            let balance = app.wrap().query_balance(&admin, NATIVE_DENOM_2).unwrap();
+            assert_eq!(balance.amount, Uint128::from(NATIVE_BALANCE_2));
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::from(BASE_PRICE * 2));
+        }
+
+        #[test]
+        fn part_of_year_is_priced_pro_rata() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let half_year = year_to_secs(1) / 2;
+            let register_err = |app: &mut App, durations: u64| {
+                app.execute_contract(
+                    admin.clone(),
+                    manager.clone(),
+                    &register_msg("tiennv", durations),
+                    &base_fee(BASE_PRICE * 10),
+                )
+                .unwrap_err()
+                .downcast::<ContractError>()
+                .unwrap()
+                .to_string()
+            };
+            assert_eq!(
+                register_err(&mut app, MIN_DURATIONS - 1),
+                ContractError::InvalidDurations.to_string()
+            );
+            assert_eq!(
+                register_err(&mut app, year_to_secs(5) + 1),
+                ContractError::LimitYearRegister.to_string()
+            );
+
+            // Min durations must be above zero and not longer than the max year register
+            for min_durations in [0, year_to_secs(5) + 1] {
+                let msg = update_config_msg(&app, &manager, |config| {
+                    config.min_durations = min_durations
+                });
+                let err = app
+                    .execute_contract(admin.clone(), manager.clone(), &msg, &[])
+                    .unwrap_err();
+                assert_eq!(
+                    err.downcast::<ContractError>().unwrap().to_string(),
+                    ContractError::InvalidArguments.to_string()
+                );
+            }
+            let res: QuoteResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::Quote {
+                        name: "tiennv".to_owned(),
+                        durations: MIN_DURATIONS,
+                        denom: Denom::Native(NATIVE_DENOM.to_owned()),
+                        sender: None,
+                    },
+                )
+                .unwrap();
+            assert!(res.accepted);
+            // 30 days of the base price is 8219.17, part of a unit is rounded up
+            assert_eq!(res.total, Uint128::from(8_220u128));
+
+            // One and a half years is charged one and a half year prices
+            let durations = year_to_secs(1) + half_year;
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("tiennv", durations),
+                &base_fee(BASE_PRICE * 10),
+            )
+            .unwrap();
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::from(BASE_PRICE * 3 / 2));
+            let expires = app.block_info().time.seconds() + durations;
+            let nft_info = |app: &App| {
+                let res: cw721::NftInfoResponse<Metadata> = app
+                    .wrap()
+                    .query_wasm_smart(
+                        name_contract(app, manager.as_str()),
+                        &aurans_name::QueryMsg::NftInfo {
+                            token_id: "tiennv".to_owned(),
+                        },
+                    )
+                    .unwrap();
+                res.extension
+            };
+            let metadata = nft_info(&app);
+            assert_eq!(metadata.durations, durations);
+            assert_eq!(metadata.expires, Some(expires));
+
+            // Extend half a year is charged half a year price
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &extend_msg("tiennv", half_year),
+                &base_fee(BASE_PRICE * 10),
+            )
+            .unwrap();
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::from(BASE_PRICE * 2));
+            let metadata = nft_info(&app);
+            assert_eq!(metadata.durations, durations + half_year);
+            assert_eq!(metadata.expires, Some(expires + half_year));
+        }
+
+        #[test]
+        fn register_with_any_accepted_denom() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let base_price_2 = Coin {
+                denom: NATIVE_DENOM_2.to_owned(),
+                amount: Uint128::from(BASE_PRICE / 2),
+            };
+
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &ExecuteMsg::UpdatePrices {
+                    prices: vec![(0, base_price_2.clone())],
+                },
+                &[],
+            )
+            .unwrap();
+            let prices: PricesResponse = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Prices {})
+                .unwrap();
+            assert!(prices.prices.contains(&(0, base_price_2.clone())));
+            assert!(prices
+                .prices
+                .contains(&(0, base_fee(BASE_PRICE)[0].clone())));
+
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("tiennv", year_to_secs(1)),
+                std::slice::from_ref(&base_price_2),
+            )
+            .unwrap();
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM_2).unwrap();
+            assert_eq!(balance, base_price_2);
+
+            // Removed denom is not accepted anymore
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &ExecuteMsg::RemovePrices {
+                    prices: vec![(0, NATIVE_DENOM_2.to_owned())],
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                admin,
+                manager,
+                &register_msg("aurans", year_to_secs(1)),
+                &[base_price_2],
+            )
+            .unwrap_err();
+        }
+
+        #[test]
+        fn multibyte_names_are_priced_by_chars() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let two_chars_price = BASE_PRICE * 50;
+            update_name_policy(
+                &mut app,
+                &manager,
+                NamePolicy {
+                    charset: Charset::Unicode,
+                    ..NamePolicy::default()
+                },
+            );
+
+            let quote = |app: &App, name: &str| -> Uint128 {
+                let quote: QuoteResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        &manager,
+                        &QueryMsg::Quote {
+                            name: name.to_owned(),
+                            durations: year_to_secs(1),
+                            denom: Denom::Native(NATIVE_DENOM.to_owned()),
+                            sender: None,
+                        },
+                    )
+                    .unwrap();
+                quote.price
+            };
+            // 6 and 8 bytes but 2 chars
+            assert_eq!(quote(&app, "名前"), Uint128::from(two_chars_price));
+            assert_eq!(quote(&app, "🦀🦀"), Uint128::from(two_chars_price));
+            assert_eq!(quote(&app, "ab"), Uint128::from(two_chars_price));
+            assert_eq!(quote(&app, "名前名"), Uint128::from(BASE_PRICE * 20));
+            assert_eq!(quote(&app, &"a".repeat(300)), Uint128::from(BASE_PRICE));
+
+            // 2 chars name can not be registered at 6 chars price
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("名前", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap_err();
+            app.execute_contract(
+                admin,
+                manager.clone(),
+                &register_msg("名前", year_to_secs(1)),
+                &base_fee(two_chars_price),
+            )
+            .unwrap();
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::from(two_chars_price));
+        }
+
+        fn cw20_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+            let res: BalanceResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    token,
+                    &cw20::Cw20QueryMsg::Balance {
+                        address: address.to_owned(),
+                    },
+                )
+                .unwrap();
+            res.balance
+        }
+
+        fn send_msg(manager: &Addr, amount: u128, msg: &ReceiveMsg) -> Cw20ExecuteMsg {
+            Cw20ExecuteMsg::Send {
+                contract: manager.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(msg).unwrap(),
+            }
+        }
+
+        #[test]
+        fn register_and_extend_with_cw20() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+
+            let token_code_id = app.store_code(cw20_contract_template());
+            let token = app
+                .instantiate_contract(
+                    token_code_id,
+                    admin.clone(),
+                    &cw20_base::msg::InstantiateMsg {
+                        name: "Aura Token".to_owned(),
+                        symbol: "TAURA".to_owned(),
+                        decimals: 6,
+                        initial_balances: vec![Cw20Coin {
+                            address: ADMIN.to_owned(),
+                            amount: Uint128::from(NATIVE_BALANCE),
+                        }],
+                        mint: None,
+                        marketing: None,
+                    },
+                    &[],
+                    "aura token",
+                    None,
+                )
+                .unwrap();
+            let register = ReceiveMsg::Register {
+                name: "tiennv".to_owned(),
+                backend_signature: None,
+                metadata: Metadata {
+                    bech32_prefixes: vec!["aura".to_owned()],
+                    durations: year_to_secs(1),
+                    ..Metadata::default()
+                },
+                secret: None,
+                owner: None,
+                referrer: None,
+            };
+
+            // Token is not whitelisted yet
+            app.execute_contract(
+                admin.clone(),
+                token.clone(),
+                &send_msg(&manager, BASE_PRICE, &register),
+                &[],
+            )
+            .unwrap_err();
+
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &ExecuteMsg::UpdateCw20Prices {
+                    prices: vec![(
+                        0,
+                        Cw20Coin {
+                            address: token.to_string(),
+                            amount: Uint128::from(BASE_PRICE),
+                        },
+                    )],
+                },
+                &[],
+            )
+            .unwrap();
+            let prices: Cw20PricesResponse = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Cw20Prices {})
+                .unwrap();
+            assert_eq!(prices.prices.len(), 1);
+            let quote: QuoteResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::Quote {
+                        name: "tiennv".to_owned(),
+                        durations: year_to_secs(1),
+                        denom: Denom::Cw20(token.clone()),
+                        sender: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(quote.total, Uint128::from(BASE_PRICE));
+
+            // Overpayment is refunded in the same token
+            app.execute_contract(
+                admin.clone(),
+                token.clone(),
+                &send_msg(&manager, BASE_PRICE * 2, &register),
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                admin.clone(),
+                token.clone(),
+                &send_msg(
+                    &manager,
+                    BASE_PRICE,
+                    &ReceiveMsg::Extend {
+                        name: "tiennv".to_owned(),
+                        backend_signature: None,
+                        durations: year_to_secs(1),
+                        referrer: None,
+                    },
+                ),
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                cw20_balance(&app, &token, manager.as_str()),
+                Uint128::from(BASE_PRICE * 2)
+            );
+            assert_eq!(
+                cw20_balance(&app, &token, ADMIN),
+                Uint128::from(NATIVE_BALANCE - BASE_PRICE * 2)
+            );
+
+            // Admin withdraws cw20 balance of manager
+            app.execute_contract(
+                admin,
+                manager.clone(),
+                &ExecuteMsg::Withdraw {
+                    receiver: USER_1.to_owned(),
+                    coin: WithdrawCoin::Cw20(Cw20Coin {
+                        address: token.to_string(),
+                        amount: Uint128::from(BASE_PRICE * 2),
+                    }),
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                cw20_balance(&app, &token, USER_1),
+                Uint128::from(BASE_PRICE * 2)
+            );
+            assert_eq!(
+                cw20_balance(&app, &token, manager.as_str()),
+                Uint128::zero()
+            );
+        }
+    }
+
+    mod policy_testing {
+        use super::*;
+
+        #[test]
+        fn register_normalizes_and_validates_name() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+
+            // Uppercase name is registered as lowercase
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("TienNV", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            assert_eq!(
+                registration_status(&app, manager.as_str(), "tiennv"),
+                RegistrationStatus::Active
+            );
+            assert_eq!(
+                registration_status(&app, manager.as_str(), "TienNV"),
+                RegistrationStatus::Active
+            );
+            let has_register: bool = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::HasRegister {
+                        name: "TIENNV".to_owned(),
+                    },
+                )
+                .unwrap();
+            assert!(has_register);
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &extend_msg("TIENNV", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            for name in [
+                "tien.nv", "tien nv", "tien\nnv", "-tiennv", "tiennv-", "tien--nv", "名前", "",
+            ] {
+                app.execute_contract(
+                    admin.clone(),
+                    manager.clone(),
+                    &register_msg(name, year_to_secs(1)),
+                    &base_fee(BASE_PRICE),
+                )
+                .unwrap_err();
+            }
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("tien-nv", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            // Uppercase is rejected when normalization is disabled
+            update_name_policy(
+                &mut app,
+                &manager,
+                NamePolicy {
+                    lowercase: false,
+                    ..NamePolicy::default()
+                },
+            );
+            app.execute_contract(
+                admin,
+                manager,
+                &register_msg("Aurans", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap_err();
+        }
+
+        #[test]
+        fn unicode_rejects_invisible_chars() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            update_name_policy(
+                &mut app,
+                &manager,
+                NamePolicy {
+                    charset: Charset::Unicode,
+                    ..NamePolicy::default()
+                },
+            );
+
+            // Zero width space and joiner, right to left override, soft hyphen,
+            // byte order mark, variation selector, hangul filler and tag
+            for name in [
+                "tien\u{200B}nv",
+                "tien\u{200D}nv",
+                "\u{202E}vnneit",
+                "tien\u{00AD}nv",
+                "\u{FEFF}tiennv",
+                "名\u{FE0F}前",
+                "\u{3164}",
+                "tiennv\u{E0041}",
+            ] {
+                let err = app
+                    .execute_contract(
+                        admin.clone(),
+                        manager.clone(),
+                        &register_msg(name, year_to_secs(1)),
+                        &base_fee(BASE_PRICE * 100),
+                    )
+                    .unwrap_err();
+                assert_eq!(
+                    err.downcast::<ContractError>().unwrap().to_string(),
+                    ContractError::InvalidName {
+                        name: name.to_owned()
+                    }
+                    .to_string()
+                );
+            }
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("名前", year_to_secs(1)),
+                &base_fee(BASE_PRICE * 100),
+            )
+            .unwrap();
+
+            // A decomposed name is composed to NFC, so it is the same name as the
+            // precomposed one
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("caf\u{E9}", year_to_secs(1)),
+                &base_fee(BASE_PRICE * 100),
+            )
+            .unwrap();
+            let err = app
+                .execute_contract(
+                    admin,
+                    manager.clone(),
+                    &register_msg("cafe\u{301}", year_to_secs(1)),
+                    &base_fee(BASE_PRICE * 100),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::NameRegistered {
+                    name: "caf\u{E9}".to_owned()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                registration_status(&app, manager.as_str(), "cafe\u{301}"),
+                registration_status(&app, manager.as_str(), "caf\u{E9}")
+            );
+        }
+
+        #[test]
+        fn mint_rejects_invalid_token_id() {
+            let (mut app, contracts) = instantiate_contracts();
+            let name_contract = name_contract(&app, &contracts[0].contract_addr);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    name_contract,
+                    &aurans_name::ExecuteMsg::Mint {
+                        token_id: "Tien.NV".to_owned(),
+                        owner: ADMIN.to_owned(),
+                        token_uri: None,
+                        extension: Metadata::default(),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<aurans_name::error::ContractError>()
+                    .unwrap()
+                    .to_string(),
+                aurans_name::error::ContractError::InvalidTokenId {}.to_string()
+            );
+        }
+
+        #[test]
+        fn tightened_policy_keeps_renewals() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("tien-nv", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            update_name_policy(
+                &mut app,
+                &manager,
+                NamePolicy {
+                    allow_hyphen: false,
+                    max_length: 4,
+                    ..NamePolicy::default()
+                },
+            );
+
+            // New names are validated by the tightened policy
+            let err = app
+                .execute_contract(
+                    admin.clone(),
+                    manager.clone(),
+                    &register_msg("au-ra", year_to_secs(1)),
+                    &base_fee(BASE_PRICE),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::InvalidName {
+                    name: "au-ra".to_owned()
+                }
+                .to_string()
+            );
+
+            // Registered names are still renewed
+            app.execute_contract(
+                admin,
+                manager.clone(),
+                &extend_msg("tien-nv", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            assert_eq!(
+                registration_status(&app, manager.as_str(), "tien-nv"),
+                RegistrationStatus::Active
+            );
+
+            // Only minter renews names in place
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    name_contract(&app, manager.as_str()),
+                    &aurans_name::ExecuteMsg::Extension {
+                        msg: aurans_name::NameExecuteMsg::Renew {
+                            token_id: "tien-nv".to_owned(),
+                            expires: u64::MAX,
+                            durations: year_to_secs(1),
+                        },
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<aurans_name::error::ContractError>()
+                    .unwrap()
+                    .to_string(),
+                aurans_name::error::ContractError::Unauthorized {
+                    sender: ADMIN.to_owned()
+                }
+                .to_string()
+            );
+        }
+    }
+
+    mod reserved_testing {
+        use super::*;
+
+        #[test]
+        fn reserved_names_are_registered_by_claimant_or_admin() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let user = Addr::unchecked(USER_1);
+
+            // Only admin can reserve names
+            let reserve_msg = ExecuteMsg::ReserveNames {
+                names: vec![
+                    ("aura".to_owned(), None),
+                    ("Validator".to_owned(), Some(USER_1.to_owned())),
+                ],
+            };
+            app.execute_contract(user.clone(), manager.clone(), &reserve_msg, &[])
+                .unwrap_err();
+            app.execute_contract(admin.clone(), manager.clone(), &reserve_msg, &[])
+                .unwrap();
+
+            let reserved: Option<ReservedName> = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::Reserved {
+                        name: "validator".to_owned(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(reserved.unwrap().claimant, Some(user.clone()));
+            let page: ReservedNamesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::ReservedNames {
+                        start_after: None,
+                        limit: Some(1),
+                    },
+                )
+                .unwrap();
+            assert_eq!(page.names.len(), 1);
+            assert_eq!(page.names[0].name, "aura");
+            let page: ReservedNamesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::ReservedNames {
+                        start_after: Some("aura".to_owned()),
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(page.names.len(), 1);
+            assert_eq!(page.names[0].name, "validator");
+
+            let err = app
+                .execute_contract(
+                    user.clone(),
+                    manager.clone(),
+                    &signed_register_msg(&app, &manager, USER_1, "aura", 0),
+                    &base_fee(BASE_PRICE * 10),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::NameReserved {
+                    name: "aura".to_owned()
+                }
+                .to_string()
+            );
+            app.execute_contract(
+                user,
+                manager.clone(),
+                &signed_register_msg(&app, &manager, USER_1, "validator", 0),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &register_msg("aura", year_to_secs(1)),
+                &base_fee(BASE_PRICE * 10),
+            )
+            .unwrap();
+
+            app.execute_contract(
+                admin,
+                manager.clone(),
+                &ExecuteMsg::UnreserveNames {
+                    names: vec!["aura".to_owned()],
+                },
+                &[],
+            )
+            .unwrap();
+            let reserved: Option<ReservedName> = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::Reserved {
+                        name: "aura".to_owned(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(reserved, None);
+        }
+
+        #[test]
+        fn mixed_case_names_are_unreserved() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let reserved = |app: &App, name: &str| -> Option<ReservedName> {
+                app.wrap()
+                    .query_wasm_smart(
+                        &manager,
+                        &QueryMsg::Reserved {
+                            name: name.to_owned(),
+                        },
+                    )
+                    .unwrap()
+            };
+
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &ExecuteMsg::ReserveNames {
+                    names: vec![("Validator".to_owned(), None)],
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                reserved(&app, "Validator"),
+                Some(ReservedName {
+                    name: "validator".to_owned(),
+                    claimant: None
+                })
+            );
+
+            let res = app
+                .execute_contract(
+                    admin,
+                    manager.clone(),
+                    &ExecuteMsg::UnreserveNames {
+                        names: vec!["VALIDATOR".to_owned()],
+                    },
+                    &[],
+                )
+                .unwrap();
+            assert!(res.has_event(
+                &Event::new("wasm")
+                    .add_attribute("action", "unreserve_names")
+                    .add_attribute("names", "validator")
+            ));
+            assert_eq!(reserved(&app, "validator"), None);
+        }
+    }
+
+    mod signature_testing {
+        use super::*;
+
+        #[test]
+        fn signature_can_not_be_replayed() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let user = Addr::unchecked(USER_1);
+
+            let msg = signed_register_msg(&app, &manager, USER_1, "tiennv", 0);
+            app.execute_contract(user, manager.clone(), &msg, &base_fee(BASE_PRICE))
+                .unwrap();
+            let nonce: u64 = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::Nonce {
+                        address: USER_1.to_owned(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(nonce, 1);
+
+            // Same signature for another name is rejected by the used nonce
+            let msg = match msg {
+                ExecuteMsg::Register {
+                    backend_signature,
+                    metadata,
+                    ..
+                } => ExecuteMsg::Register {
+                    name: "aurans".to_owned(),
+                    backend_signature,
+                    metadata,
+                    secret: None,
+                    owner: None,
+                    referrer: None,
+                },
+                _ => unreachable!(),
+            };
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::InvalidNonce { expected: 1 }.to_string()
+            );
+        }
+
+        #[test]
+        fn expired_or_foreign_signature_is_rejected() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+
+            let msg = signed_register_msg(&app, &manager, USER_1, "tiennv", 0);
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(601);
+            });
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::SignatureExpired.to_string()
+            );
+
+            // Signature for another manager contract
+            let msg = signed_register_msg(&app, &Addr::unchecked(ADMIN), USER_1, "tiennv", 0);
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::InvalidSignature.to_string()
+            );
+        }
+
+        #[test]
+        fn rotated_keys_overlap_and_removed_key_is_rejected() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let user = Addr::unchecked(USER_1);
+            let now = app.block_info().time.seconds();
+            let new_signer = signer_at(1);
+
+            // Rotate to a new key, the old key is still valid for a while
+            for verifier in [
+                Verifier {
+                    key_id: BACKEND_KEY_ID.to_owned(),
+                    backend_pubkey: Binary(backend_signer().public_key().to_bytes()),
+                    algorithm: KeyAlgorithm::Secp256k1,
+                    scheme: SignScheme::Raw,
+                    not_before: 0,
+                    not_after: Some(now + 100),
+                },
+                Verifier {
+                    key_id: "backend-2".to_owned(),
+                    backend_pubkey: Binary(new_signer.public_key().to_bytes()),
+                    algorithm: KeyAlgorithm::Secp256k1,
+                    scheme: SignScheme::Raw,
+                    not_before: now,
+                    not_after: None,
+                },
+            ] {
+                app.execute_contract(
+                    admin.clone(),
+                    manager.clone(),
+                    &ExecuteMsg::UpdateVerifier { verifier },
+                    &[],
+                )
+                .unwrap();
+            }
+
+            app.execute_contract(
+                user.clone(),
+                manager.clone(),
+                &signed_register_msg(&app, &manager, USER_1, "tiennv", 0),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            app.execute_contract(
+                user.clone(),
+                manager.clone(),
+                &signed_register_msg_with_key(
+                    &app,
+                    &manager,
+                    USER_1,
+                    "aurans",
+                    1,
+                    &[("backend-2", &new_signer)],
+                ),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(101);
+            });
+            let msg = signed_register_msg(&app, &manager, USER_1, "aurans-1", 2);
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::VerifierNotActive {
+                    key_id: BACKEND_KEY_ID.to_owned()
+                }
+                .to_string()
+            );
+
+            // Removed key is rejected immediately
+            app.execute_contract(
+                admin,
+                manager.clone(),
+                &ExecuteMsg::RemoveVerifier {
+                    key_id: "backend-2".to_owned(),
+                },
+                &[],
+            )
+            .unwrap();
+            let msg = signed_register_msg_with_key(
+                &app,
+                &manager,
+                USER_1,
+                "aurans-1",
+                2,
+                &[("backend-2", &new_signer)],
+            );
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::VerifierNotFound {
+                    key_id: "backend-2".to_owned()
+                }
+                .to_string()
+            );
+            let verifiers: Vec<Verifier> = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Verifiers {})
+                .unwrap();
+            assert_eq!(verifiers.len(), 1);
+        }
+
+        #[test]
+        fn short_names_require_threshold_signatures() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let user = Addr::unchecked(USER_1);
+            let second_signer = signer_at(1);
+
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &ExecuteMsg::UpdateVerifier {
+                    verifier: Verifier {
+                        key_id: "backend-2".to_owned(),
+                        backend_pubkey: Binary(second_signer.public_key().to_bytes()),
+                        algorithm: KeyAlgorithm::Secp256k1,
+                        scheme: SignScheme::Raw,
+                        not_before: 0,
+                        not_after: None,
+                    },
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &ExecuteMsg::UpdateThresholds {
+                    thresholds: vec![(3, 2)],
+                },
+                &[],
+            )
+            .unwrap();
+            let thresholds: Vec<(u8, u32)> = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Thresholds {})
+                .unwrap();
+            assert_eq!(thresholds, vec![(3, 2)]);
+
+            let msg = signed_register_msg(&app, &manager, USER_1, "abc", 0);
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::ThresholdNotMet {
+                    signed: 1,
+                    threshold: 2
+                }
+                .to_string()
+            );
+            let msg = signed_register_msg_with_key(
+                &app,
+                &manager,
+                USER_1,
+                "abc",
+                0,
+                &[
+                    (BACKEND_KEY_ID, &backend_signer()),
+                    (BACKEND_KEY_ID, &backend_signer()),
+                ],
+            );
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::DuplicateSignature {
+                    key_id: BACKEND_KEY_ID.to_owned()
+                }
+                .to_string()
+            );
+
+            let fee = base_fee(BASE_PRICE * 20);
+            app.execute_contract(
+                user.clone(),
+                manager.clone(),
+                &signed_register_msg_with_key(
+                    &app,
+                    &manager,
+                    USER_1,
+                    "abc",
+                    0,
+                    &[
+                        (BACKEND_KEY_ID, &backend_signer()),
+                        ("backend-2", &second_signer),
+                    ],
+                ),
+                &fee,
+            )
+            .unwrap();
+
+            // Other lengths still need one signature
+            app.execute_contract(
+                user.clone(),
+                manager.clone(),
+                &signed_register_msg(&app, &manager, USER_1, "tiennv", 1),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            // Thresholds apply to lengths priced by the base tier, zero removes a threshold
+            app.execute_contract(
+                admin,
+                manager.clone(),
+                &ExecuteMsg::UpdateThresholds {
+                    thresholds: vec![(3, 0), (7, 2)],
+                },
+                &[],
+            )
+            .unwrap();
+            let thresholds: Vec<(u8, u32)> = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Thresholds {})
+                .unwrap();
+            assert_eq!(thresholds, vec![(7, 2)]);
+            let msg = signed_register_msg(&app, &manager, USER_1, "tiennv7", 2);
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::ThresholdNotMet {
+                    signed: 1,
+                    threshold: 2
+                }
+                .to_string()
+            );
+            app.execute_contract(
+                user,
+                manager.clone(),
+                &signed_register_msg(&app, &manager, USER_1, "aur", 2),
+                &fee,
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn adr036_signature_is_accepted() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let wallet = signer_at(2);
+            let wallet_addr = wallet.public_key().account_id("aura").unwrap().to_string();
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                manager.clone(),
+                &ExecuteMsg::UpdateVerifier {
+                    verifier: Verifier {
+                        key_id: "wallet".to_owned(),
+                        backend_pubkey: Binary(wallet.public_key().to_bytes()),
+                        algorithm: KeyAlgorithm::Secp256k1,
+                        scheme: SignScheme::Adr036 {
+                            signer: wallet_addr.clone(),
+                        },
+                        not_before: 0,
+                        not_after: None,
+                    },
+                },
+                &[],
+            )
+            .unwrap();
+
+            // Amino json sign doc of `signArbitrary`
+            let verify_msg = register_verify_msg(&app, &manager, USER_1, "tiennv", 0);
+            let data = Binary::from(serde_json_wasm::to_string(&verify_msg).unwrap().as_bytes());
+            let adr036_sign = |signer: &str| {
+                let sign_doc = format!(
+                    r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"sequence":"0"}}"#,
+                    data, signer
+                );
+                KeySignature {
+                    key_id: "wallet".to_owned(),
+                    signature: Binary(wallet.sign(sign_doc.as_bytes()).unwrap().to_vec()),
+                }
+            };
+
+            // Raw signature of wallet key is not accepted
+            let msg = signed_register_msg_with_key(
+                &app,
+                &manager,
+                USER_1,
+                "tiennv",
+                0,
+                &[("wallet", &wallet)],
+            );
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::InvalidSignature.to_string()
+            );
+            let msg = register_msg_with_signatures(&verify_msg, vec![adr036_sign(USER_1)]);
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::InvalidSignature.to_string()
+            );
+
+            let msg = register_msg_with_signatures(&verify_msg, vec![adr036_sign(&wallet_addr)]);
+            app.execute_contract(
+                Addr::unchecked(USER_1),
+                manager,
+                &msg,
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn signed_price_is_charged_down_to_floor() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let priced_msg = |app: &App, price: Coin| {
+                let mut verify_msg = register_verify_msg(app, &manager, USER_1, "tiennv", 0);
+                if let VerifyMsg::Register { price: signed, .. } = &mut verify_msg {
+                    *signed = Some(price);
+                }
+                let signature = KeySignature {
+                    key_id: BACKEND_KEY_ID.to_owned(),
+                    signature: sign(&backend_signer(), &verify_msg),
+                };
+                register_msg_with_signatures(&verify_msg, vec![signature])
+            };
+
+            // Floor is half of the table price
+            let msg = priced_msg(&app, coin(BASE_PRICE / 4, NATIVE_DENOM));
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::PriceBelowFloor {
+                    price: format!("{}{}", BASE_PRICE / 4, NATIVE_DENOM),
+                    floor: format!("{}{}", BASE_PRICE / 2, NATIVE_DENOM),
+                }
+                .to_string()
+            );
+            let msg = priced_msg(&app, coin(BASE_PRICE / 2, NATIVE_DENOM_2));
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &msg,
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::InvalidDenom {
+                    denom: NATIVE_DENOM_2.to_owned()
+                }
+                .to_string()
+            );
+
+            // Signed price is charged and the discount is recorded
+            let msg = priced_msg(&app, coin(BASE_PRICE / 2, NATIVE_DENOM));
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(USER_1),
+                    manager.clone(),
+                    &msg,
+                    &base_fee(BASE_PRICE),
+                )
+                .unwrap();
+            assert!(res.has_event(
+                &Event::new("wasm")
+                    .add_attribute("discount", format!("{}{}", BASE_PRICE / 2, NATIVE_DENOM))
+            ));
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::from(BASE_PRICE / 2));
+        }
+    }
+
+    mod registration_mode_testing {
+        use super::*;
+
+        fn owner_of(app: &App, manager: &Addr, name: &str) -> String {
+            let res: cw721::OwnerOfResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    name_contract(app, manager.as_str()),
+                    &aurans_name::QueryMsg::OwnerOf {
+                        token_id: name.to_owned(),
+                        include_expired: None,
+                    },
+                )
+                .unwrap();
+            res.owner
+        }
+
+        #[test]
+        fn owner_extends_without_signature() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let user = Addr::unchecked(USER_1);
+
+            // Register requires backend signature
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &register_msg("tiennv", year_to_secs(1)),
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::SignatureRequired.to_string()
+            );
+            let msg = signed_register_msg(&app, &manager, USER_1, "tiennv", 0);
+            app.execute_contract(user.clone(), manager.clone(), &msg, &base_fee(BASE_PRICE))
+                .unwrap();
+            app.execute_contract(
+                user.clone(),
+                manager.clone(),
+                &extend_msg("tiennv", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            // Others still need backend signature to extend
+            app.execute_contract(
+                admin,
+                manager.clone(),
+                &register_msg("aurans", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &extend_msg("aurans", year_to_secs(1)),
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::SignatureRequired.to_string()
+            );
+        }
+
+        #[test]
+        fn open_for_renewals_keeps_owner() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let user = Addr::unchecked(USER_1);
+            update_config(&mut app, &manager, |config| {
+                config.registration_mode = RegistrationMode::OpenForRenewals
+            });
+
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &register_msg("tiennv", year_to_secs(1)),
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::SignatureRequired.to_string()
+            );
+
+            // Anyone can pay to extend a name, the owner is unchanged
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                manager.clone(),
+                &register_msg("aurans", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            app.execute_contract(
+                user,
+                manager.clone(),
+                &extend_msg("aurans", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            assert_eq!(owner_of(&app, &manager, "aurans"), ADMIN);
+        }
+
+        #[test]
+        fn open_registration_is_checked_on_chain() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let user = Addr::unchecked(USER_1);
+            update_config(&mut app, &manager, |config| {
+                config.registration_mode = RegistrationMode::Open
+            });
+
+            app.execute_contract(
+                user.clone(),
+                manager.clone(),
+                &register_msg("tiennv", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            assert_eq!(owner_of(&app, &manager, "tiennv"), USER_1);
+
+            // Name policy and reserved list still apply
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &register_msg("tien nv", year_to_secs(1)),
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::InvalidName {
+                    name: "tien nv".to_owned()
+                }
+                .to_string()
+            );
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                manager.clone(),
+                &ExecuteMsg::ReserveNames {
+                    names: vec![("aurans".to_owned(), None)],
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                execute_err(
+                    &mut app,
+                    USER_1,
+                    &manager,
+                    &register_msg("aurans", year_to_secs(1)),
+                    &base_fee(BASE_PRICE * 100)
+                ),
+                ContractError::NameReserved {
+                    name: "aurans".to_owned()
+                }
+                .to_string()
+            );
+        }
+    }
+
+    mod revenue_testing {
+        use super::*;
+        use crate::state::{ReferralReward, Revenue, RevenueShare};
+
+        #[test]
+        fn distribute_splits_fees_to_revenue_shares() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let recipients: Vec<String> = (3..6)
+                .map(|i| {
+                    signer_at(i)
+                        .public_key()
+                        .account_id("aura")
+                        .unwrap()
+                        .to_string()
+                })
+                .collect();
+
+            // Basis points must sum to 10000
+            let update_msg = |bps: &[u64]| ExecuteMsg::UpdateRevenueShares {
+                shares: recipients
+                    .iter()
+                    .cloned()
+                    .zip(bps.iter().cloned())
+                    .collect(),
+            };
+            let err = app
+                .execute_contract(
+                    admin.clone(),
+                    manager.clone(),
+                    &update_msg(&[5000, 3000]),
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::InvalidRevenueShares.to_string()
+            );
+            app.execute_contract(
+                Addr::unchecked(USER_1),
+                manager.clone(),
+                &update_msg(&[3333, 3333, 3334]),
+                &[],
+            )
+            .unwrap_err();
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &update_msg(&[3333, 3333, 3334]),
+                &[],
+            )
+            .unwrap();
+            let shares: Vec<RevenueShare> = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::RevenueShares {})
+                .unwrap();
+            assert_eq!(shares.len(), 3);
+
+            app.execute_contract(
+                admin,
+                manager.clone(),
+                &register_msg("tiennv", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+
+            // Anyone can distribute, the last recipient takes the rounding remainder
+            app.execute_contract(
+                Addr::unchecked(USER_1),
+                manager.clone(),
+                &ExecuteMsg::Distribute {},
+                &[],
+            )
+            .unwrap();
+            let balances: Vec<u128> = recipients
+                .iter()
+                .map(|recipient| {
+                    app.wrap()
+                        .query_balance(recipient, NATIVE_DENOM)
+                        .unwrap()
+                        .amount
+                        .u128()
+                })
+                .collect();
+            assert_eq!(balances, vec![33_330, 33_330, 33_340]);
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM).unwrap();
+            assert!(balance.amount.is_zero());
+
+            let revenues: Vec<Revenue> = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Revenues {})
+                .unwrap();
+            assert_eq!(
+                revenues,
+                vec![Revenue {
+                    denom: Denom::Native(NATIVE_DENOM.to_owned()),
+                    accumulated: Uint128::from(BASE_PRICE),
+                    distributed: Uint128::from(BASE_PRICE),
+                }]
+            );
+        }
+
+        #[test]
+        fn referrers_claim_commissions() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let partner = Addr::unchecked(
+                signer_at(3)
+                    .public_key()
+                    .account_id("aura")
+                    .unwrap()
+                    .to_string(),
+            );
+            let treasury = signer_at(4)
+                .public_key()
+                .account_id("aura")
+                .unwrap()
+                .to_string();
+
+            let rates_msg = ExecuteMsg::UpdateReferralRates {
+                rates: vec![(partner.to_string(), 1000)],
+            };
+            app.execute_contract(Addr::unchecked(USER_1), manager.clone(), &rates_msg, &[])
+                .unwrap_err();
+            app.execute_contract(admin.clone(), manager.clone(), &rates_msg, &[])
+                .unwrap();
+            app.execute_contract(
+                admin.clone(),
+                manager.clone(),
+                &ExecuteMsg::UpdateRevenueShares {
+                    shares: vec![(treasury.clone(), 10000)],
+                },
+                &[],
+            )
+            .unwrap();
+
+            let msg = match register_msg("tiennv", year_to_secs(1)) {
+                ExecuteMsg::Register {
+                    name,
+                    backend_signature,
+                    metadata,
+                    secret,
+                    owner,
+                    ..
+                } => ExecuteMsg::Register {
+                    name,
+                    backend_signature,
+                    metadata,
+                    secret,
+                    owner,
+                    referrer: Some(partner.to_string()),
+                },
+                _ => unreachable!(),
+            };
+            let res = app
+                .execute_contract(admin.clone(), manager.clone(), &msg, &base_fee(BASE_PRICE))
+                .unwrap();
+            assert!(res.has_event(
+                &Event::new("wasm")
+                    .add_attribute("commission", format!("{}{}", BASE_PRICE / 10, NATIVE_DENOM))
+            ));
+
+            // Commissions are kept out of the distribution
+            app.execute_contract(
+                Addr::unchecked(USER_1),
+                manager.clone(),
+                &ExecuteMsg::Distribute {},
+                &[],
+            )
+            .unwrap();
+            let balance = app.wrap().query_balance(&treasury, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::from(BASE_PRICE * 9 / 10));
+
+            // Admin can not withdraw the commissions
+            let err = app
+                .execute_contract(
+                    admin,
+                    manager.clone(),
+                    &ExecuteMsg::Withdraw {
+                        receiver: ADMIN.to_owned(),
+                        coin: WithdrawCoin::Native(coin(1, NATIVE_DENOM)),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::WithdrawExceedsBalance {
+                    available: format!("0{}", NATIVE_DENOM)
+                }
+                .to_string()
+            );
+
+            let claim_msg = ExecuteMsg::ClaimReferralRewards {};
+            app.execute_contract(partner.clone(), manager.clone(), &claim_msg, &[])
+                .unwrap();
+            let balance = app.wrap().query_balance(&partner, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::from(BASE_PRICE / 10));
+            let rewards: Vec<ReferralReward> = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::ReferralRewards {
+                        referrer: partner.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                rewards,
+                vec![ReferralReward {
+                    denom: Denom::Native(NATIVE_DENOM.to_owned()),
+                    accrued: Uint128::from(BASE_PRICE / 10),
+                    claimed: Uint128::from(BASE_PRICE / 10),
+                }]
+            );
+            let err = app
+                .execute_contract(partner, manager.clone(), &claim_msg, &[])
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::NoReferralRewards.to_string()
+            );
+        }
+
+        #[test]
+        fn burn_share_of_fees() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            update_config(&mut app, &manager, |config| config.burn_bps = 2000);
+
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    manager.clone(),
+                    &register_msg("tiennv", year_to_secs(1)),
+                    &base_fee(BASE_PRICE),
+                )
+                .unwrap();
+            assert!(res.has_event(
+                &Event::new("wasm")
+                    .add_attribute("burned", format!("{}{}", BASE_PRICE / 5, NATIVE_DENOM))
+            ));
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::from(BASE_PRICE * 4 / 5));
+
+            let burned: Vec<Coin> = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Burned {})
+                .unwrap();
+            assert_eq!(burned, vec![coin(BASE_PRICE / 5, NATIVE_DENOM)]);
+            let revenues: Vec<Revenue> = app
+                .wrap()
+                .query_wasm_smart(&manager, &QueryMsg::Revenues {})
+                .unwrap();
+            assert_eq!(revenues[0].accumulated, Uint128::from(BASE_PRICE * 4 / 5));
+        }
+    }
+
+    mod batch_testing {
+        use super::*;
+        use crate::msg::RegisterItem;
+
+        fn register_item(msg: ExecuteMsg) -> RegisterItem {
+            match msg {
+                ExecuteMsg::Register {
+                    name,
+                    backend_signature,
+                    metadata,
+                    secret,
+                    owner,
+                    ..
+                } => RegisterItem {
+                    name,
+                    backend_signature,
+                    metadata,
+                    secret,
+                    owner,
+                },
+                _ => unreachable!(),
+            }
+        }
+
+        fn batch_msg(items: Vec<RegisterItem>) -> ExecuteMsg {
+            ExecuteMsg::RegisterBatch {
+                items,
+                referrer: None,
+            }
+        }
+
+        #[test]
+        fn register_batch_charges_funds_once() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let admin = Addr::unchecked(ADMIN);
+            let names = ["tiennv", "aurans", "cosmos"];
+            let items = || {
+                names
+                    .iter()
+                    .map(|name| register_item(register_msg(name, year_to_secs(1))))
+                    .collect::<Vec<RegisterItem>>()
+            };
+
+            let err = app
+                .execute_contract(
+                    admin.clone(),
+                    manager.clone(),
+                    &batch_msg(items()),
+                    &base_fee(BASE_PRICE * 2),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::InsufficientFunds.to_string()
+            );
+            assert!(!app
+                .wrap()
+                .query_wasm_smart::<bool>(
+                    &manager,
+                    &QueryMsg::HasRegister {
+                        name: "tiennv".to_owned()
+                    }
+                )
+                .unwrap());
+
+            // Overpayment is refunded once
+            let res = app
+                .execute_contract(
+                    admin.clone(),
+                    manager.clone(),
+                    &batch_msg(items()),
+                    &base_fee(BASE_PRICE * 4),
+                )
+                .unwrap();
+            assert_eq!(
+                res.events
+                    .iter()
+                    .filter(|event| event.ty == "wasm-register")
+                    .count(),
+                3
+            );
+            let balance = app.wrap().query_balance(&manager, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::from(BASE_PRICE * 3));
+            for name in names {
+                assert_eq!(
+                    registration_status(&app, manager.as_str(), name),
+                    RegistrationStatus::Active
+                );
+            }
+
+            let items = (0..11)
+                .map(|i| register_item(register_msg(&format!("name-{}", i), year_to_secs(1))))
+                .collect();
+            let err = app
+                .execute_contract(
+                    admin,
+                    manager,
+                    &batch_msg(items),
+                    &base_fee(BASE_PRICE * 11),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::BatchTooLong.to_string()
+            );
+        }
+
+        #[test]
+        fn register_batch_with_sequential_nonces() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+
+            let items = vec![
+                register_item(signed_register_msg(&app, &manager, USER_1, "tiennv", 0)),
+                register_item(signed_register_msg(&app, &manager, USER_1, "aurans", 1)),
+            ];
+            app.execute_contract(
+                Addr::unchecked(USER_1),
+                manager.clone(),
+                &batch_msg(items),
+                &base_fee(BASE_PRICE * 2),
+            )
+            .unwrap();
+            let nonce: u64 = app
+                .wrap()
+                .query_wasm_smart(
+                    &manager,
+                    &QueryMsg::Nonce {
+                        address: USER_1.to_owned(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(nonce, 2);
+        }
+
+        #[test]
+        fn extend_batch_renews_owned_names() {
+            let (mut app, contracts) = instantiate_contracts();
+            let manager = Addr::unchecked(&contracts[0].contract_addr);
+            let user = Addr::unchecked(USER_1);
+
+            let items = vec![
+                register_item(signed_register_msg(&app, &manager, USER_1, "tiennv", 0)),
+                register_item(signed_register_msg(&app, &manager, USER_1, "aurans", 1)),
+            ];
+            app.execute_contract(
+                user.clone(),
+                manager.clone(),
+                &batch_msg(items),
+                &base_fee(BASE_PRICE * 2),
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                manager.clone(),
+                &register_msg("cosmos", year_to_secs(1)),
+                &base_fee(BASE_PRICE),
+            )
+            .unwrap();
+            let expires = |app: &App, name: &str| {
+                let res: RegistrationStatusResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        &manager,
+                        &QueryMsg::RegistrationStatus {
+                            name: name.to_owned(),
+                        },
+                    )
+                    .unwrap();
+                res.expires.unwrap()
+            };
+            let tiennv_expires = expires(&app, "tiennv");
+            let aurans_expires = expires(&app, "aurans");
+
+            // Names of others need backend signatures, nothing is extended
+            let extend_batch = |names: &[(&str, u64)]| ExecuteMsg::ExtendBatch {
+                names: names
+                    .iter()
+                    .map(|(name, years)| (name.to_string(), year_to_secs(*years)))
+                    .collect(),
+                referrer: None,
+            };
+            let err = app
+                .execute_contract(
+                    user.clone(),
+                    manager.clone(),
+                    &extend_batch(&[("tiennv", 1), ("cosmos", 1)]),
+                    &base_fee(BASE_PRICE * 2),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::SignatureRequired.to_string()
+            );
+            assert_eq!(expires(&app, "tiennv"), tiennv_expires);
+
+            // Same name can not be extended twice in a batch
+            let err = app
+                .execute_contract(
+                    user.clone(),
+                    manager.clone(),
+                    &extend_batch(&[("tiennv", 1), ("TienNV", 1)]),
+                    &base_fee(BASE_PRICE * 2),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap().to_string(),
+                ContractError::InvalidArguments.to_string()
+            );
+
+            let res = app
+                .execute_contract(
+                    user,
+                    manager.clone(),
+                    &extend_batch(&[("tiennv", 1), ("aurans", 2)]),
+                    &base_fee(BASE_PRICE * 3),
+                )
+                .unwrap();
+            let new_expires = tiennv_expires + year_to_secs(1);
+            assert!(res.has_event(
+                &Event::new("wasm-extend")
+                    .add_attribute("name", "tiennv")
+                    .add_attribute("new_expires", new_expires.to_string())
+            ));
+            assert_eq!(expires(&app, "tiennv"), new_expires);
+            assert_eq!(expires(&app, "aurans"), aurans_expires + year_to_secs(2));
+        }
+    }
 }
@@ -1,6 +1,18 @@
+use crate::{msg::RegistrationStatus, state::Config};
+
 // 365 days
 pub const SEC_PER_YEAR: u64 = 31536000;
 
+// Denominator of the fees configured in basis points
+pub const BPS_DENOMINATOR: u64 = 10000;
+
+// Length of name used for pricing is the number of unicode scalar values (chars),
+// not utf-8 bytes, so "名前" and "ab" are both 2. The backend signer must count the
+// same way. Names longer than 255 chars are counted as 255
+pub fn name_length(name: &str) -> u8 {
+    name.chars().count().min(u8::MAX as usize) as u8
+}
+
 pub fn year_to_secs(year: u64) -> u64 {
     year * SEC_PER_YEAR
 }
@@ -8,3 +20,21 @@ pub fn year_to_secs(year: u64) -> u64 {
 pub fn sec_to_years(sec: u64) -> u64 {
     sec / SEC_PER_YEAR
 }
+
+// Time the name is released after its grace and redemption periods
+pub fn release_time(config: &Config, expires: u64) -> u64 {
+    expires + config.grace_period + config.redemption_period
+}
+
+pub fn registration_status(config: &Config, expires: u64, now: u64) -> RegistrationStatus {
+    let grace_ends = expires + config.grace_period;
+    if now < expires {
+        RegistrationStatus::Active
+    } else if now < grace_ends {
+        RegistrationStatus::Grace
+    } else if now < release_time(config, expires) {
+        RegistrationStatus::Redemption
+    } else {
+        RegistrationStatus::Available
+    }
+}
@@ -1,30 +1,178 @@
 use crate::error::ContractError;
-use cosmwasm_std::Deps;
+use crate::msg::{BackendSignature, VerifyMsg};
+use crate::state::{KeyAlgorithm, SignScheme, Verifier, NONCES, THRESHOLDS, VERIFIERS};
+use crate::util::name_length;
+use cosmwasm_std::{Addr, Binary, Deps, DepsMut, Env, StdResult};
+use serde::Serialize;
 use sha2::Digest;
 
+// ADR-036 sign doc, fields are sorted as amino json requires
+#[derive(Serialize)]
+struct Adr036SignDoc {
+    account_number: String,
+    chain_id: String,
+    fee: Adr036Fee,
+    memo: String,
+    msgs: Vec<Adr036Msg>,
+    sequence: String,
+}
+
+#[derive(Serialize)]
+struct Adr036Fee {
+    amount: Vec<String>,
+    gas: String,
+}
+
+#[derive(Serialize)]
+struct Adr036Msg {
+    #[serde(rename = "type")]
+    msg_type: String,
+    value: Adr036MsgValue,
+}
+
+#[derive(Serialize)]
+struct Adr036MsgValue {
+    data: Binary,
+    signer: String,
+}
+
+// Sign doc of `signArbitrary`, chain id, fee and sequence are empty as ADR-036 requires
+pub fn adr036_sign_doc(signer: &str, data: &[u8]) -> Result<String, ContractError> {
+    let sign_doc = Adr036SignDoc {
+        account_number: "0".to_owned(),
+        chain_id: "".to_owned(),
+        fee: Adr036Fee {
+            amount: vec![],
+            gas: "0".to_owned(),
+        },
+        memo: "".to_owned(),
+        msgs: vec![Adr036Msg {
+            msg_type: "sign/MsgSignData".to_owned(),
+            value: Adr036MsgValue {
+                data: Binary::from(data),
+                signer: signer.to_owned(),
+            },
+        }],
+        sequence: "0".to_owned(),
+    };
+    serde_json_wasm::to_string(&sign_doc).map_err(|_| ContractError::SerdeError)
+}
+
+// Secp256r1 keys can not be verified until the VM supports it
+pub fn check_verifier(verifier: &Verifier) -> Result<(), ContractError> {
+    if verifier.algorithm == KeyAlgorithm::Secp256r1 {
+        return Err(ContractError::UnsupportedAlgorithm {
+            algorithm: verifier.algorithm.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Number of backend signatures required for name, configured by its length in chars
+// so names without a price of their own length are covered too
+pub fn signature_threshold(deps: Deps, name: &str) -> StdResult<u32> {
+    Ok(THRESHOLDS
+        .may_load(deps.storage, name_length(name))?
+        .unwrap_or(1))
+}
+
+// Verify backend signatures of msg, the signatures must not be expired and must
+// carry the nonce of sender, the nonce is increased so it can not be replayed.
+// Every signature must be valid and signed by a distinct active key, and there
+// must be at least the threshold of the name signatures
 pub fn verify_signature(
-    deps: Deps,
-    msg: &str,
-    signature: &[u8],
-    pubkey: &[u8],
-) -> Result<bool, ContractError> {
-    let msg_hash = sha2::Sha256::digest(msg);
-    let ok = deps
-        .api
-        .secp256k1_verify(&msg_hash, signature, pubkey)
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    msg: &VerifyMsg,
+    backend_signature: &BackendSignature,
+) -> Result<(), ContractError> {
+    if env.block.time.seconds() > backend_signature.valid_until {
+        return Err(ContractError::SignatureExpired);
+    }
+    let nonce = NONCES.may_load(deps.storage, sender)?.unwrap_or_default();
+    if backend_signature.nonce != nonce {
+        return Err(ContractError::InvalidNonce { expected: nonce });
+    }
+
+    let name = match msg {
+        VerifyMsg::Register { name, .. } | VerifyMsg::Extend { name, .. } => name,
+    };
+    let threshold = signature_threshold(deps.as_ref(), name)?;
+    let signed = backend_signature.signatures.len() as u32;
+    if signed < threshold {
+        return Err(ContractError::ThresholdNotMet { signed, threshold });
+    }
+
+    let msg = serde_json_wasm::to_string(msg).map_err(|_| ContractError::SerdeError)?;
+    let now = env.block.time.seconds();
+    let mut key_ids: Vec<&String> = Vec::new();
+    for key_signature in &backend_signature.signatures {
+        let key_id = &key_signature.key_id;
+        if key_ids.contains(&key_id) {
+            return Err(ContractError::DuplicateSignature {
+                key_id: key_id.clone(),
+            });
+        }
+        key_ids.push(key_id);
+
+        // Key must be active at current block time
+        let verifier = VERIFIERS.may_load(deps.storage, key_id)?.ok_or_else(|| {
+            ContractError::VerifierNotFound {
+                key_id: key_id.clone(),
+            }
+        })?;
+        if now < verifier.not_before
+            || matches!(verifier.not_after, Some(not_after) if now > not_after)
+        {
+            return Err(ContractError::VerifierNotActive {
+                key_id: key_id.clone(),
+            });
+        }
+
+        let payload = match &verifier.scheme {
+            SignScheme::Raw => msg.clone(),
+            SignScheme::Adr036 { signer } => adr036_sign_doc(signer, msg.as_bytes())?,
+        };
+        // Secp256k1 signs sha256 of payload, ed25519 signs the payload itself
+        let ok = match verifier.algorithm {
+            KeyAlgorithm::Secp256k1 => deps.api.secp256k1_verify(
+                &sha2::Sha256::digest(&payload),
+                &key_signature.signature,
+                &verifier.backend_pubkey,
+            ),
+            KeyAlgorithm::Ed25519 => deps.api.ed25519_verify(
+                payload.as_bytes(),
+                &key_signature.signature,
+                &verifier.backend_pubkey,
+            ),
+            KeyAlgorithm::Secp256r1 => {
+                return Err(ContractError::UnsupportedAlgorithm {
+                    algorithm: verifier.algorithm.to_string(),
+                })
+            }
+        }
         .map_err(|_| ContractError::VerificationError)?;
-    if !ok {
-        return Err(ContractError::InvalidSignature);
+        if !ok {
+            return Err(ContractError::InvalidSignature);
+        }
     }
-    Ok(true)
+
+    NONCES.save(deps.storage, sender, &(nonce + 1))?;
+    Ok(())
 }
 
 #[cfg(test)]
 pub mod tests {
     use cosmrs::{bip32, crypto::secp256k1::SigningKey, tendermint::crypto::Sha256};
     use cosmwasm_crypto::secp256k1_verify;
-    use cosmwasm_std::Binary;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{Addr, Binary};
 
+    use super::{check_verifier, verify_signature};
+    use crate::error::ContractError;
+    use crate::msg::{BackendSignature, KeySignature};
+    use crate::state::{KeyAlgorithm, SignScheme, Verifier, VERIFIERS};
     use crate::{msg::VerifyMsg, util::year_to_secs};
 
     fn from_mnemonic(phrase: &str, derivation_path: &str) -> SigningKey {
@@ -57,8 +205,12 @@ pub mod tests {
             name: "tiennv".to_owned(),
             sender: "aura1yntfxtwysmgjp6wzza590xctjpzne3ak9scynv".to_owned(),
             chain_id: "aura-local".to_owned(),
+            contract: "aura14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9sdkpzsr".to_owned(),
             bech32_prefixes: vec!["aura".to_owned(), "cosmos".to_owned()],
             durations: one_year,
+            nonce: 0,
+            valid_until: 1_700_000_000,
+            price: None,
         };
 
         let register_msg_json = serde_json_wasm::to_string(&register_msg).unwrap();
@@ -80,7 +232,11 @@ pub mod tests {
             name: "tiennv".to_owned(),
             sender: "aura1yntfxtwysmgjp6wzza590xctjpzne3ak9scynv".to_string(),
             chain_id: "aura-local".to_owned(),
+            contract: "aura14hj2tavq8fpesdwxxcu44rty3hh90vhujrvcmstl4zr3txmfvw9sdkpzsr".to_owned(),
             durations: one_year,
+            nonce: 1,
+            valid_until: 1_700_000_000,
+            price: None,
         };
 
         let extend_msg_json = serde_json_wasm::to_string(&extend_msg).unwrap();
@@ -88,4 +244,120 @@ pub mod tests {
         let sig_binary = Binary(sig.clone());
         println!("sig={:?}", sig_binary.to_string());
     }
+
+    #[test]
+    fn test_ed25519_and_secp256r1() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let sender = Addr::unchecked("sender");
+
+        // Deterministic key of the hsm
+        let signing_key = ed25519_zebra::SigningKey::from([7u8; 32]);
+        let pubkey: [u8; 32] = ed25519_zebra::VerificationKey::from(&signing_key).into();
+        let verifier = Verifier {
+            key_id: "hsm".to_owned(),
+            backend_pubkey: Binary(pubkey.to_vec()),
+            algorithm: KeyAlgorithm::Ed25519,
+            scheme: SignScheme::Raw,
+            not_before: 0,
+            not_after: None,
+        };
+        check_verifier(&verifier).unwrap();
+        VERIFIERS
+            .save(deps.as_mut().storage, "hsm", &verifier)
+            .unwrap();
+
+        let valid_until = env.block.time.seconds() + 600;
+        let extend_msg = VerifyMsg::Extend {
+            name: "tiennv".to_owned(),
+            sender: sender.to_string(),
+            chain_id: env.block.chain_id.clone(),
+            contract: env.contract.address.to_string(),
+            durations: year_to_secs(1),
+            nonce: 0,
+            valid_until,
+            price: None,
+        };
+        let extend_msg_json = serde_json_wasm::to_string(&extend_msg).unwrap();
+        let sig: [u8; 64] = signing_key.sign(extend_msg_json.as_bytes()).into();
+        let backend_signature = BackendSignature {
+            signatures: vec![KeySignature {
+                key_id: "hsm".to_owned(),
+                signature: Binary(sig.to_vec()),
+            }],
+            nonce: 0,
+            valid_until,
+            price: None,
+        };
+        verify_signature(
+            deps.as_mut(),
+            &env,
+            &sender,
+            &extend_msg,
+            &backend_signature,
+        )
+        .unwrap();
+
+        // Secp256k1 key can not verify ed25519 signature of the next nonce
+        let extend_msg = VerifyMsg::Extend {
+            name: "tiennv".to_owned(),
+            sender: sender.to_string(),
+            chain_id: env.block.chain_id.clone(),
+            contract: env.contract.address.to_string(),
+            durations: year_to_secs(1),
+            nonce: 1,
+            valid_until,
+            price: None,
+        };
+        let extend_msg_json = serde_json_wasm::to_string(&extend_msg).unwrap();
+        let sig: [u8; 64] = signing_key.sign(extend_msg_json.as_bytes()).into();
+        let backend_signature = BackendSignature {
+            signatures: vec![KeySignature {
+                key_id: "hsm".to_owned(),
+                signature: Binary(sig.to_vec()),
+            }],
+            nonce: 1,
+            valid_until,
+            price: None,
+        };
+        VERIFIERS
+            .save(
+                deps.as_mut().storage,
+                "hsm",
+                &Verifier {
+                    algorithm: KeyAlgorithm::Secp256k1,
+                    ..verifier.clone()
+                },
+            )
+            .unwrap();
+        let err = verify_signature(
+            deps.as_mut(),
+            &env,
+            &sender,
+            &extend_msg,
+            &backend_signature,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::VerificationError));
+
+        // Same signature is valid with the ed25519 key
+        VERIFIERS
+            .save(deps.as_mut().storage, "hsm", &verifier)
+            .unwrap();
+        verify_signature(
+            deps.as_mut(),
+            &env,
+            &sender,
+            &extend_msg,
+            &backend_signature,
+        )
+        .unwrap();
+
+        let err = check_verifier(&Verifier {
+            algorithm: KeyAlgorithm::Secp256r1,
+            ..verifier
+        })
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedAlgorithm { .. }));
+    }
 }
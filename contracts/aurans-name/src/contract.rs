@@ -21,7 +21,8 @@ use cw_utils::parse_reply_instantiate_data;
 use std::vec;
 
 use crate::error::ContractError;
-use crate::state::{Config, Metadata, Resolver, CONFIG, RESOLVER};
+use crate::policy::NamePolicy;
+use crate::state::{Config, Metadata, Resolver, CONFIG, NAME_POLICY, RESOLVER};
 
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, NameExecuteMsg, NameQueryMsg, QueryMsg};
 
@@ -55,6 +56,7 @@ pub fn instantiate(
         minter: deps.api.addr_validate(&msg.minter)?,
     };
     CONFIG.save(deps.storage, &config)?;
+    NAME_POLICY.save(deps.storage, &msg.name_policy.unwrap_or_default())?;
 
     let name_cw721 = NameCw721::default();
 
@@ -85,7 +87,7 @@ pub fn instantiate(
     Ok(Response::new()
         .add_submessage(resolver_sub_msg)
         .add_attribute("action", "instantiate")
-        .add_attribute("admin", &msg.admin.to_string())
+        .add_attribute("admin", msg.admin.to_string())
         .add_attribute("minter", msg.minter.to_string())
         .add_attribute("resolver_code_id", msg.resolver_code_id.to_string()))
 }
@@ -134,6 +136,14 @@ pub fn execute(
             NameExecuteMsg::UpdateResolver { resolver } => {
                 execute_update_resolver(deps, env, info, resolver)
             }
+            NameExecuteMsg::UpdateNamePolicy { name_policy } => {
+                execute_update_name_policy(deps, env, info, name_policy)
+            }
+            NameExecuteMsg::Renew {
+                token_id,
+                expires,
+                durations,
+            } => execute_renew(deps, env, info, token_id, expires, durations),
         },
         msg @ Approve { .. }
         | msg @ ApproveAll { .. }
@@ -192,6 +202,12 @@ fn execute_mint(
     token_uri: Option<String>,
     extension: Metadata,
 ) -> Result<Response, ContractError> {
+    // Minter must normalize the name before minting
+    let name_policy = NAME_POLICY.load(deps.storage)?;
+    if !name_policy.is_valid(&token_id) {
+        return Err(ContractError::InvalidTokenId {});
+    }
+
     let resolver = RESOLVER.load(deps.as_ref().storage)?;
     let name_cw721 = NameCw721::default();
 
@@ -217,6 +233,7 @@ fn execute_mint(
         name: token_id.to_owned(),
         bech32_prefixes: extension.clone().bech32_prefixes,
         address: owner.clone(),
+        expires: extension.expires,
     };
     let update_resolver_msg = WasmMsg::Execute {
         contract_addr: resolver.address.to_string(),
@@ -234,6 +251,50 @@ fn execute_mint(
         .add_attribute("bech32_prefixes", extension.bech32_prefixes.join(",")))
 }
 
+// Names registered under an older policy are renewed in place, so they are kept
+// by their owners when the policy is tightened
+fn execute_renew(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    token_id: String,
+    expires: u64,
+    durations: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.minter {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+
+    let resolver = RESOLVER.load(deps.storage)?;
+    let name_cw721 = NameCw721::default();
+    let mut token = name_cw721.tokens.load(deps.storage, &token_id)?;
+    token.extension.durations += durations;
+    token.extension.expires = Some(expires);
+    name_cw721.tokens.save(deps.storage, &token_id, &token)?;
+
+    let update_record = UpdateRecord {
+        name: token_id.to_owned(),
+        bech32_prefixes: token.extension.bech32_prefixes.clone(),
+        address: token.owner.to_string(),
+        expires: token.extension.expires,
+    };
+    let update_resolver_msg = WasmMsg::Execute {
+        contract_addr: resolver.address.to_string(),
+        msg: to_binary(&update_record)?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_message(update_resolver_msg)
+        .add_attribute("action", "renew")
+        .add_attribute("token_id", token_id)
+        .add_attribute("expires", expires.to_string())
+        .add_attribute("durations", token.extension.durations.to_string()))
+}
+
 fn execute_transfer_nft(
     deps: DepsMut,
     env: Env,
@@ -243,6 +304,7 @@ fn execute_transfer_nft(
 ) -> Result<Response, ContractError> {
     let resolver = RESOLVER.load(deps.as_ref().storage)?;
     let name_cw721 = NameCw721::default();
+    check_not_expired(deps.as_ref(), &env, &token_id)?;
     let token = name_cw721._transfer_nft(deps, &env, &info, &recipient, &token_id)?;
     let metadata = token.extension;
 
@@ -250,6 +312,7 @@ fn execute_transfer_nft(
         name: token_id.to_owned(),
         bech32_prefixes: metadata.bech32_prefixes,
         address: recipient.clone(),
+        expires: metadata.expires,
     };
     let update_resolver_msg = WasmMsg::Execute {
         contract_addr: resolver.address.to_string(),
@@ -275,6 +338,7 @@ fn execute_send_nft(
 ) -> Result<Response, ContractError> {
     let resolver = RESOLVER.load(deps.as_ref().storage)?;
     let name_cw721 = NameCw721::default();
+    check_not_expired(deps.as_ref(), &env, &token_id)?;
     let token = name_cw721._transfer_nft(deps, &env, &info, &contract, &token_id)?;
     let send = Cw721ReceiveMsg {
         sender: info.sender.to_string(),
@@ -287,6 +351,7 @@ fn execute_send_nft(
         name: token_id.to_owned(),
         bech32_prefixes: metadata.bech32_prefixes,
         address: contract.clone(),
+        expires: metadata.expires,
     };
     let update_resolver_msg = WasmMsg::Execute {
         contract_addr: resolver.address.to_string(),
@@ -303,6 +368,20 @@ fn execute_send_nft(
         .add_attribute("token_id", token_id))
 }
 
+// Expired names can not be transferred until they are registered again
+fn check_not_expired(deps: Deps, env: &Env, token_id: &str) -> Result<(), ContractError> {
+    let name_cw721 = NameCw721::default();
+    let token = name_cw721.tokens.load(deps.storage, token_id)?;
+    if let Some(expires) = token.extension.expires {
+        if expires <= env.block.time.seconds() {
+            return Err(ContractError::NameExpired {
+                token_id: token_id.to_owned(),
+            });
+        }
+    }
+    Ok(())
+}
+
 fn execute_update_resolver(
     deps: DepsMut,
     _env: Env,
@@ -327,6 +406,28 @@ fn execute_update_resolver(
         .add_attribute("resolver", resolver))
 }
 
+fn execute_update_name_policy(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name_policy: NamePolicy,
+) -> Result<Response, ContractError> {
+    // only contract admin can update name policy
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {
+            sender: info.sender.clone().to_string(),
+        });
+    }
+
+    NAME_POLICY.save(deps.storage, &name_policy)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_name_policy")
+        .add_attribute("min_length", name_policy.min_length.to_string())
+        .add_attribute("max_length", name_policy.max_length.to_string()))
+}
+
 fn execute_update_config(
     deps: DepsMut,
     _env: Env,
@@ -374,7 +475,7 @@ fn execute_burn_tokens(
 
     let name_cw721 = NameCw721::default();
     for token_id in &token_ids {
-        name_cw721.tokens.remove(deps.storage, &token_id)?;
+        name_cw721.tokens.remove(deps.storage, token_id)?;
         name_cw721.decrement_tokens(deps.storage)?;
     }
     // Delete records has burn to resolver
@@ -404,6 +505,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QExtension { msg } => match msg {
             NameQueryMsg::Config {} => to_binary(&query_config(deps)?),
             NameQueryMsg::Resolver {} => to_binary(&query_resolver(deps)?),
+            NameQueryMsg::NamePolicy {} => to_binary(&query_name_policy(deps)?),
         },
         _ => {
             let name_cw721 = NameCw721::default();
@@ -416,6 +518,10 @@ fn query_resolver(deps: Deps) -> StdResult<Resolver> {
     RESOLVER.load(deps.storage)
 }
 
+fn query_name_policy(deps: Deps) -> StdResult<NamePolicy> {
+    NAME_POLICY.load(deps.storage)
+}
+
 fn query_config(deps: Deps) -> StdResult<Config> {
     CONFIG.load(deps.storage)
 }
@@ -24,4 +24,7 @@ pub enum ContractError {
 
     #[error("Invalid token id")]
     InvalidTokenId {},
+
+    #[error("Name expired: {token_id:?}")]
+    NameExpired { token_id: String },
 }
@@ -1,6 +1,7 @@
 pub mod contract;
 pub mod error;
 pub mod msg;
+pub mod policy;
 pub mod state;
 
 pub use crate::msg::*;
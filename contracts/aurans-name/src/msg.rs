@@ -1,6 +1,7 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::CustomMsg;
 
+use crate::policy::NamePolicy;
 use crate::state::{Config, Metadata, Resolver};
 
 /// Message type for `instantiate` entry_point
@@ -13,6 +14,9 @@ pub struct InstantiateMsg {
     /// This is designed for a base NFT that is controlled by an external program
     /// or contract. You will likely replace this with custom logic in custom NFTs
     pub minter: String,
+
+    /// Validation policy of minted names, default policy if not set
+    pub name_policy: Option<NamePolicy>,
 }
 
 /// Message type for `execute` entry_point
@@ -27,10 +31,20 @@ pub enum NameExecuteMsg {
     UpdateResolver {
         resolver: String,
     },
+    UpdateNamePolicy {
+        name_policy: NamePolicy,
+    },
     BurnTokens {
         // Should be limit batch size
         token_ids: Vec<String>,
     },
+    // Renew a minted name in place to the new expires, durations are added to its
+    // lifetime. Renewals are not checked by the name policy, only minter can renew
+    Renew {
+        token_id: String,
+        expires: u64,
+        durations: u64,
+    },
 }
 
 /// Message type for `query` entry_point
@@ -44,6 +58,9 @@ pub enum NameQueryMsg {
 
     #[returns(Resolver)]
     Resolver {},
+
+    #[returns(NamePolicy)]
+    NamePolicy {},
 }
 
 impl CustomMsg for NameExecuteMsg {}
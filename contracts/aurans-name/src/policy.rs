@@ -0,0 +1,128 @@
+use cosmwasm_schema::cw_serde;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+#[cw_serde]
+pub enum Charset {
+    // Lowercase ascii letters and digits
+    Ascii,
+    // Ascii charset plus any non-ascii char except whitespace, control, format and
+    // default ignorable chars
+    Unicode,
+}
+
+// Format (Cf) and default ignorable code points, they are invisible or change how
+// the name is displayed, so a name with them could spoof another name
+const INVISIBLE_CHARS: &[(char, char)] = &[
+    ('\u{00AD}', '\u{00AD}'),
+    ('\u{034F}', '\u{034F}'),
+    ('\u{0600}', '\u{0605}'),
+    ('\u{061C}', '\u{061C}'),
+    ('\u{06DD}', '\u{06DD}'),
+    ('\u{070F}', '\u{070F}'),
+    ('\u{0890}', '\u{0891}'),
+    ('\u{08E2}', '\u{08E2}'),
+    ('\u{115F}', '\u{1160}'),
+    ('\u{17B4}', '\u{17B5}'),
+    ('\u{180B}', '\u{180F}'),
+    ('\u{200B}', '\u{200F}'),
+    ('\u{202A}', '\u{202E}'),
+    ('\u{2060}', '\u{206F}'),
+    ('\u{3164}', '\u{3164}'),
+    ('\u{FE00}', '\u{FE0F}'),
+    ('\u{FEFF}', '\u{FEFF}'),
+    ('\u{FFA0}', '\u{FFA0}'),
+    ('\u{FFF0}', '\u{FFFB}'),
+    ('\u{110BD}', '\u{110BD}'),
+    ('\u{110CD}', '\u{110CD}'),
+    ('\u{13430}', '\u{1343F}'),
+    ('\u{1BCA0}', '\u{1BCA3}'),
+    ('\u{1D173}', '\u{1D17A}'),
+    ('\u{E0000}', '\u{E0FFF}'),
+];
+
+fn is_invisible(c: char) -> bool {
+    INVISIBLE_CHARS
+        .iter()
+        .any(|(start, end)| (*start..=*end).contains(&c))
+}
+
+// Rules a name must follow to be minted, hyphen is the only allowed punctuation
+// so names never contain dots of the `name.prefix` format
+#[cw_serde]
+pub struct NamePolicy {
+    pub charset: Charset,
+    // Length in unicode chars, same as the manager counts for pricing
+    pub min_length: u8,
+    pub max_length: u8,
+    pub allow_hyphen: bool,
+    // Allow hyphen at the start or end of name
+    pub allow_edge_hyphen: bool,
+    pub allow_consecutive_hyphens: bool,
+    // Normalize uppercase names to lowercase on register instead of rejecting them
+    pub lowercase: bool,
+}
+
+impl Default for NamePolicy {
+    fn default() -> Self {
+        NamePolicy {
+            charset: Charset::Ascii,
+            min_length: 1,
+            max_length: 63,
+            allow_hyphen: true,
+            allow_edge_hyphen: false,
+            allow_consecutive_hyphens: false,
+            lowercase: true,
+        }
+    }
+}
+
+impl NamePolicy {
+    // Compose to NFC so "e\u{301}" and "\u{e9}" are the same name
+    pub fn normalize(&self, name: &str) -> String {
+        if self.lowercase {
+            name.to_lowercase().nfc().collect()
+        } else {
+            name.nfc().collect()
+        }
+    }
+
+    // Normalized names are always lowercase NFC, uppercase chars and decomposed
+    // forms are invalid
+    pub fn is_valid(&self, name: &str) -> bool {
+        if !is_nfc(name) {
+            return false;
+        }
+        let length = name.chars().count();
+        if length < self.min_length as usize || length > self.max_length as usize {
+            return false;
+        }
+        if name.contains('-') {
+            if !self.allow_hyphen {
+                return false;
+            }
+            if !self.allow_edge_hyphen && (name.starts_with('-') || name.ends_with('-')) {
+                return false;
+            }
+            if !self.allow_consecutive_hyphens && name.contains("--") {
+                return false;
+            }
+        }
+        name.chars().all(|c| self.is_valid_char(c))
+    }
+
+    fn is_valid_char(&self, c: char) -> bool {
+        if c == '-' || c.is_ascii_lowercase() || c.is_ascii_digit() {
+            return true;
+        }
+        match self.charset {
+            Charset::Ascii => false,
+            Charset::Unicode => {
+                !c.is_ascii()
+                    && !c.is_uppercase()
+                    && !c.is_whitespace()
+                    && !c.is_control()
+                    && !is_invisible(c)
+            }
+        }
+    }
+}
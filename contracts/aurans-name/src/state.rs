@@ -2,6 +2,8 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Addr;
 use cw_storage_plus::Item;
 
+use crate::policy::NamePolicy;
+
 #[cw_serde]
 pub struct Trait {
     pub display_type: Option<String>,
@@ -37,6 +39,9 @@ pub struct Metadata {
     // Lifetime duration of nft in seconds
     pub durations: u64,
 
+    // Expiration time of nft in seconds, set by the minter
+    pub expires: Option<u64>,
+
     pub collection_name: Option<String>,
     pub collection_symbol: Option<String>,
 }
@@ -54,3 +59,4 @@ pub struct Resolver {
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const RESOLVER: Item<Resolver> = Item::new("resolver");
+pub const NAME_POLICY: Item<NamePolicy> = Item::new("name_policy");
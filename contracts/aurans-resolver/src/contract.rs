@@ -2,7 +2,7 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::StdError;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
@@ -10,7 +10,7 @@ use cw_storage_plus::KeyDeserialize;
 
 use crate::error::ContractError;
 use crate::state::IGNORE_ADDRS;
-use crate::state::{records, Config, CONFIG, NAME_CONTRACT};
+use crate::state::{records, Config, CONFIG, EXPIRES, NAME_CONTRACT};
 
 use crate::msg::{
     AddressResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, NamesResponse, QueryMsg,
@@ -70,7 +70,8 @@ pub fn execute(
             name,
             bech32_prefixes,
             address,
-        } => execute_update_record(deps, env, info, name, bech32_prefixes, address),
+            expires,
+        } => execute_update_record(deps, env, info, name, bech32_prefixes, address, expires),
         ExecuteMsg::UpdateNameContract { name_contract } => {
             execute_update_name_contract(deps, env, info, name_contract)
         }
@@ -85,7 +86,7 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::NameContract {} => to_binary(&query_name_contract(deps)?),
@@ -95,13 +96,14 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::AddressOf {
             primary_name,
             bech32_prefix,
-        } => to_binary(&query_address_of(deps, primary_name, bech32_prefix)?),
+        } => to_binary(&query_address_of(deps, env, primary_name, bech32_prefix)?),
         QueryMsg::AllAddressesOf {
             primary_name,
             start_after,
             limit,
         } => to_binary(&query_all_addresses_of(
             deps,
+            env,
             primary_name,
             start_after,
             limit,
@@ -110,7 +112,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             owner,
             start_after,
             limit,
-        } => to_binary(&query_names(deps, owner, start_after, limit)?),
+        } => to_binary(&query_names(deps, env, owner, start_after, limit)?),
     }
 }
 
@@ -199,6 +201,7 @@ fn execute_update_record(
     name: String,
     bech32_prefixes: Vec<String>,
     address: String,
+    expires: Option<u64>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     can_execute(deps.as_ref(), &config, &info.sender)?;
@@ -206,13 +209,21 @@ fn execute_update_record(
     for bech32_prefix in &bech32_prefixes {
         let bech32_addr_decoded = util::bech32_decode(&address)?;
         let bech32_addr = util::bech32_encode(bech32_prefix, &bech32_addr_decoded);
-        records().save(deps.storage, (&name, &bech32_prefix), &bech32_addr)?;
+        records().save(deps.storage, (&name, bech32_prefix), &bech32_addr)?;
+    }
+    match expires {
+        Some(expires) => EXPIRES.save(deps.storage, &name, &expires)?,
+        None => EXPIRES.remove(deps.storage, &name),
     }
     Ok(Response::new()
         .add_attribute("action", "update_record")
         .add_attribute("name", &name)
-        .add_attribute("bech32_prefixes", &bech32_prefixes.join(","))
-        .add_attribute("address", &address))
+        .add_attribute("bech32_prefixes", bech32_prefixes.join(","))
+        .add_attribute("address", &address)
+        .add_attribute(
+            "expires",
+            expires.map_or("none".to_owned(), |e| e.to_string()),
+        ))
 }
 
 fn execute_delete_names(
@@ -226,6 +237,7 @@ fn execute_delete_names(
 
     for name in &names {
         records().prefix(name).clear(deps.storage, None);
+        EXPIRES.remove(deps.storage, name);
     }
 
     Ok(Response::new()
@@ -248,9 +260,13 @@ fn query_is_ignore_address(deps: Deps, address: String) -> StdResult<bool> {
 
 fn query_address_of(
     deps: Deps,
+    env: Env,
     primary_name: String,
     bech32_prefix: String,
 ) -> StdResult<AddressResponse> {
+    if is_expired(deps.storage, &env, &primary_name) {
+        return Err(StdError::generic_err("name expired"));
+    }
     let key = (primary_name.as_ref(), bech32_prefix.as_ref());
     let address = records().load(deps.storage, key)?;
     if IGNORE_ADDRS.has(deps.storage, &address) {
@@ -264,10 +280,14 @@ fn query_address_of(
 
 fn query_all_addresses_of(
     deps: Deps,
+    env: Env,
     primary_name: String,
     start_after: Option<String>,
     limit: Option<u32>,
 ) -> StdResult<Vec<AddressResponse>> {
+    if is_expired(deps.storage, &env, &primary_name) {
+        return Ok(vec![]);
+    }
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
 
@@ -295,6 +315,7 @@ fn query_all_addresses_of(
 
 fn query_names(
     deps: Deps,
+    env: Env,
     owner: String,
     start_after: Option<String>,
     limit: Option<u32>,
@@ -305,32 +326,50 @@ fn query_names(
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
 
-    let names = records()
+    // Scan at most limit records so records of expired names can not make a page
+    // unbounded, a page may hold less names than limit before the last page
+    let keys = records()
         .idx
         .address
         .prefix(owner)
         .keys(deps.storage, start, None, Order::Ascending)
         .take(limit)
-        .map(|result| {
-            result
-                .iter()
-                .map(|key| {
-                    let (name, bech32_prefix) = <(String, String)>::from_slice(key.as_bytes())?;
-                    Ok(format!("{}.{}", name, bech32_prefix))
-                })
-                .collect::<StdResult<String>>()
-        })
         .collect::<StdResult<Vec<_>>>()?;
-    Ok(NamesResponse { names })
+    let next_start_after = if keys.len() == limit {
+        keys.last().cloned()
+    } else {
+        None
+    };
+
+    let mut names = Vec::new();
+    for key in keys {
+        let (name, bech32_prefix) = <(String, String)>::from_slice(key.as_bytes())?;
+        // Skip records of expired names
+        if !is_expired(deps.storage, &env, &name) {
+            names.push(format!("{}.{}", name, bech32_prefix));
+        }
+    }
+    Ok(NamesResponse {
+        names,
+        next_start_after,
+    })
+}
+
+// Return true if name has expires and it is passed
+fn is_expired(storage: &dyn Storage, env: &Env, name: &str) -> bool {
+    match EXPIRES.may_load(storage, name) {
+        Ok(Some(expires)) => expires <= env.block.time.seconds(),
+        _ => false,
+    }
 }
 
 // Return true if sender is admin or address of name contract
 fn can_execute(deps: Deps, config: &Config, sender: &Addr) -> Result<bool, ContractError> {
-    if sender.to_string() == config.admin {
+    if *sender == config.admin {
         return Ok(true);
     }
     let name_contract = NAME_CONTRACT.load(deps.storage)?;
-    if sender.to_string() == name_contract {
+    if *sender == name_contract {
         return Ok(true);
     }
 
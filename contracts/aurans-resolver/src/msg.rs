@@ -22,6 +22,7 @@ pub enum ExecuteMsg {
         name: String,
         bech32_prefixes: Vec<String>,
         address: String,
+        expires: Option<u64>,
     },
     DeleteNames {
         names: Vec<String>,
@@ -81,4 +82,6 @@ pub struct AddressResponse {
 #[cw_serde]
 pub struct NamesResponse {
     pub names: Vec<String>,
+    // Start after of the next page, none if this is the last page
+    pub next_start_after: Option<String>,
 }
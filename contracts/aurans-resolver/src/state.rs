@@ -34,3 +34,5 @@ pub fn records<'a>() -> IndexedMap<'a, (&'a str, &'a str), String, RecordIndexes
 pub const NAME_CONTRACT: Item<Addr> = Item::new("name_contract");
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const IGNORE_ADDRS: Map<&str, bool> = Map::new("ignore_addrs");
+// A map primary_name with expires (seconds), expired names are not resolved
+pub const EXPIRES: Map<&str, u64> = Map::new("expires");
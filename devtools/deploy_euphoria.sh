@@ -26,7 +26,7 @@ function main() {
   manager_code_id=$(aurad query tx $manager_store_txhash --output json | jq -r '.logs[0].events[-1].attributes[-1].value')
   echo "manager_code_id: $manager_code_id"
 
-  init_manager="{\"admin\":\"$admin\",\"operator\":\"$operator\",\"max_year_register\":5,\"prices\":[[0,{\"denom\":\"ueaura\",\"amount\":\"100\"}],[1,{\"denom\":\"ueaura\",\"amount\":\"1000\"}],[2,{\"denom\":\"ueaura\",\"amount\":\"800\"}],[3,{\"denom\":\"ueaura\",\"amount\":\"500\"}],[4,{\"denom\":\"ueaura\",\"amount\":\"300\"}]],\"backend_pubkey\":\"$backend_pubkey\",\"name_code_id\":$name_code_id,\"resolver_code_id\":$resolver_code_id}"
+  init_manager="{\"admin\":\"$admin\",\"operator\":\"$operator\",\"max_year_register\":5,\"min_durations\":31536000,\"grace_period\":2592000,\"redemption_period\":2592000,\"redemption_fee_bps\":5000,\"premium_start\":[{\"denom\":\"ueaura\",\"amount\":\"10000\"}],\"premium_period\":1814400,\"min_commitment_age\":60,\"max_commitment_age\":86400,\"registration_mode\":\"signature_required\",\"price_floor_bps\":5000,\"burn_bps\":0,\"prices\":[[0,{\"denom\":\"ueaura\",\"amount\":\"100\"}],[1,{\"denom\":\"ueaura\",\"amount\":\"1000\"}],[2,{\"denom\":\"ueaura\",\"amount\":\"800\"}],[3,{\"denom\":\"ueaura\",\"amount\":\"500\"}],[4,{\"denom\":\"ueaura\",\"amount\":\"300\"}]],\"verifiers\":[{\"key_id\":\"backend-1\",\"backend_pubkey\":\"$backend_pubkey\",\"algorithm\":\"secp256k1\",\"scheme\":\"raw\",\"not_before\":0,\"not_after\":null}],\"name_code_id\":$name_code_id,\"resolver_code_id\":$resolver_code_id}"
 
   ins_manager_txhash=$(aurad tx wasm instantiate $manager_code_id "$init_manager" --label "aurans-manager" --from admin --fees 25000ueaura --gas 5000000 --output json -y --no-admin | jq -r '.txhash')
   sleep 10